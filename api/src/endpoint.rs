@@ -0,0 +1,392 @@
+use crate::entity::{
+    user, Absence, AbsenceId, Employee, EmployeeId, Group, Role, Round, Talk, TalkId, User,
+};
+use crate::pick::PickStrategy;
+use chrono::NaiveDate;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Debug;
+
+// ------ Endpoint ------
+
+/// Contract of an API endpoint, shared by the client and the server.
+///
+/// `GET` endpoints take no body, their `Request` is `()`.
+/// Except `Login`, `Groups` and `CreateGroup`, the endpoints are scoped to a group,
+/// their path is under the path of the group (see `group_path`).
+pub trait Endpoint {
+    const PATH: &'static str;
+    const METHOD: Method;
+    /// Least role allowed to call the endpoint, `None` when it's open to anonymous users.
+    const ROLE: Option<Role>;
+    type Request: Serialize + DeserializeOwned + Debug;
+    type Response: Serialize + DeserializeOwned + Debug;
+    type Error: Serialize + DeserializeOwned + Debug;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        }
+    }
+}
+
+/// Path of the endpoint `path` of the group `slug`.
+pub fn group_path(slug: &str, path: &str) -> String {
+    format!("g/{}/{}", slug, path)
+}
+
+/// Whether the `user`, `None` when nobody is logged in, may call the endpoint `E`.
+pub fn is_allowed<E: Endpoint>(user: Option<&User>) -> bool {
+    user::is_allowed(user, E::ROLE)
+}
+
+/// Slug of the group and path of the endpoint, from a path made by `group_path`.
+pub fn split_group_path(path: &str) -> Option<(&str, &str)> {
+    let mut parts = path.strip_prefix("g/")?.splitn(2, '/');
+    Some((parts.next()?, parts.next()?))
+}
+
+// ------ Payloads ------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub name: String,
+    pub password: String,
+}
+
+/// Sent back by `Login`, the token goes in the `Authorization: Bearer` header of the next requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub token: String,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddGroup {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddEmployee {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportEmployees {
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveEmployee {
+    pub employee_id: EmployeeId,
+}
+
+/// Changes the fields which are set, leaves the others untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateEmployee {
+    pub employee_id: EmployeeId,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub inactive: Option<bool>,
+    #[serde(default)]
+    pub picked: Option<bool>,
+}
+
+impl UpdateEmployee {
+    pub fn new(employee_id: EmployeeId) -> Self {
+        Self {
+            employee_id,
+            name: None,
+            inactive: None,
+            picked: None,
+        }
+    }
+}
+
+/// Draws a candidate among the ones not picked yet, except the `excluded` ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawEmployee {
+    pub strategy: PickStrategy,
+    pub excluded: Vec<EmployeeId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptPick {
+    pub employee_id: EmployeeId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmTalk {
+    pub talk_id: TalkId,
+    pub title: String,
+    pub slides: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescheduleTalk {
+    pub talk_id: TalkId,
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelTalk {
+    pub talk_id: TalkId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclareAbsence {
+    pub employee_id: EmployeeId,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawAbsence {
+    pub absence_id: AbsenceId,
+}
+
+/// Error payload sent by the server along with a 4xx or 5xx status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub errors: Vec<String>,
+}
+
+impl ErrorBody {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self {
+            errors: vec![error.into()],
+        }
+    }
+}
+
+/// All the employees, and the one just picked.
+pub type Picked = (Vec<Employee>, Employee);
+
+/// All the employees and all the talks, most recent first.
+pub type TalkHistory = (Vec<Employee>, Vec<Talk>);
+
+/// All the employees and the current round.
+pub type RoundState = (Vec<Employee>, Round);
+
+// ------ Endpoints ------
+
+pub struct Login;
+
+impl Endpoint for Login {
+    const PATH: &'static str = "login";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = None;
+    type Request = Credentials;
+    type Response = Session;
+    type Error = ErrorBody;
+}
+
+pub struct Groups;
+
+impl Endpoint for Groups {
+    const PATH: &'static str = "groups";
+    const METHOD: Method = Method::Get;
+    const ROLE: Option<Role> = Some(Role::Viewer);
+    type Request = ();
+    type Response = Vec<Group>;
+    type Error = ErrorBody;
+}
+
+pub struct CreateGroup;
+
+impl Endpoint for CreateGroup {
+    const PATH: &'static str = "groups/add";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = AddGroup;
+    type Response = Vec<Group>;
+    type Error = ErrorBody;
+}
+
+pub struct List;
+
+impl Endpoint for List {
+    const PATH: &'static str = "list";
+    const METHOD: Method = Method::Get;
+    const ROLE: Option<Role> = Some(Role::Viewer);
+    type Request = ();
+    type Response = Vec<Employee>;
+    type Error = ErrorBody;
+}
+
+pub struct Add;
+
+impl Endpoint for Add {
+    const PATH: &'static str = "add";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = AddEmployee;
+    type Response = Vec<Employee>;
+    type Error = ErrorBody;
+}
+
+pub struct Import;
+
+impl Endpoint for Import {
+    const PATH: &'static str = "import";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = ImportEmployees;
+    type Response = Vec<Employee>;
+    type Error = ErrorBody;
+}
+
+pub struct Remove;
+
+impl Endpoint for Remove {
+    const PATH: &'static str = "remove";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = RemoveEmployee;
+    type Response = Vec<Employee>;
+    type Error = ErrorBody;
+}
+
+pub struct Update;
+
+impl Endpoint for Update {
+    const PATH: &'static str = "update";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = UpdateEmployee;
+    type Response = Vec<Employee>;
+    type Error = ErrorBody;
+}
+
+/// Nothing is persisted, the candidate is picked only once accepted.
+pub struct Draw;
+
+impl Endpoint for Draw {
+    const PATH: &'static str = "pick/draw";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Organiser);
+    type Request = DrawEmployee;
+    type Response = Option<Employee>;
+    type Error = ErrorBody;
+}
+
+pub struct Accept;
+
+impl Endpoint for Accept {
+    const PATH: &'static str = "pick/accept";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Organiser);
+    type Request = AcceptPick;
+    type Response = Picked;
+    type Error = ErrorBody;
+}
+
+pub struct Talks;
+
+impl Endpoint for Talks {
+    const PATH: &'static str = "talks";
+    const METHOD: Method = Method::Get;
+    const ROLE: Option<Role> = Some(Role::Viewer);
+    type Request = ();
+    type Response = TalkHistory;
+    type Error = ErrorBody;
+}
+
+pub struct Confirm;
+
+impl Endpoint for Confirm {
+    const PATH: &'static str = "talks/confirm";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Organiser);
+    type Request = ConfirmTalk;
+    type Response = TalkHistory;
+    type Error = ErrorBody;
+}
+
+pub struct Reschedule;
+
+impl Endpoint for Reschedule {
+    const PATH: &'static str = "talks/reschedule";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Organiser);
+    type Request = RescheduleTalk;
+    type Response = TalkHistory;
+    type Error = ErrorBody;
+}
+
+pub struct Cancel;
+
+impl Endpoint for Cancel {
+    const PATH: &'static str = "talks/cancel";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Organiser);
+    type Request = CancelTalk;
+    type Response = TalkHistory;
+    type Error = ErrorBody;
+}
+
+pub struct CurrentRound;
+
+impl Endpoint for CurrentRound {
+    const PATH: &'static str = "round";
+    const METHOD: Method = Method::Get;
+    const ROLE: Option<Role> = Some(Role::Viewer);
+    type Request = ();
+    type Response = RoundState;
+    type Error = ErrorBody;
+}
+
+pub struct NewRound;
+
+impl Endpoint for NewRound {
+    const PATH: &'static str = "round/new";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = ();
+    type Response = RoundState;
+    type Error = ErrorBody;
+}
+
+/// Sorted by start date.
+pub struct Absences;
+
+impl Endpoint for Absences {
+    const PATH: &'static str = "absences";
+    const METHOD: Method = Method::Get;
+    const ROLE: Option<Role> = Some(Role::Viewer);
+    type Request = ();
+    type Response = Vec<Absence>;
+    type Error = ErrorBody;
+}
+
+pub struct Declare;
+
+impl Endpoint for Declare {
+    const PATH: &'static str = "absences/add";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = DeclareAbsence;
+    type Response = Vec<Absence>;
+    type Error = ErrorBody;
+}
+
+pub struct Withdraw;
+
+impl Endpoint for Withdraw {
+    const PATH: &'static str = "absences/remove";
+    const METHOD: Method = Method::Post;
+    const ROLE: Option<Role> = Some(Role::Admin);
+    type Request = WithdrawAbsence;
+    type Response = Vec<Absence>;
+    type Error = ErrorBody;
+}
@@ -0,0 +1,33 @@
+use super::EmployeeId;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub type AbsenceId = Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Absence {
+    pub uuid: AbsenceId,
+    pub employee_id: EmployeeId,
+    pub from: NaiveDate,
+    /// Last day of the absence, included.
+    pub to: NaiveDate,
+    pub reason: String,
+}
+
+impl Absence {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    /// The day the employee is back.
+    pub fn return_date(&self) -> NaiveDate {
+        self.to.succ_opt().unwrap_or(self.to)
+    }
+}
+
+/// The day absences are checked against. The UTC date, so that the server and the browsers,
+/// whatever their time zone, agree on who is absent.
+pub fn today() -> NaiveDate {
+    Utc::now().date_naive()
+}
@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub type EmployeeId = Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Employee {
+    pub uuid: EmployeeId,
+    pub name: String,
+    pub picked: bool,
+    /// Inactive employees are kept but never picked.
+    #[serde(default)]
+    pub inactive: bool,
+    #[serde(default)]
+    pub joined_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_picked_at: Option<DateTime<Utc>>,
+}
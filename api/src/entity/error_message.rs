@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use shrinkwraprs::Shrinkwrap;
+use std::borrow::Cow;
+use std::fmt;
+
+// ------ ErrorKind ------
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ErrorKind {
+    /// The server couldn't be reached.
+    Network,
+    /// The server didn't answer in time.
+    Timeout,
+    /// The server rejected the request (4xx).
+    Validation,
+    /// The server failed to handle the request (5xx).
+    Server,
+    /// The response couldn't be decoded.
+    Decode,
+}
+
+impl ErrorKind {
+    /// Whether the same request may succeed if it's sent again.
+    pub fn is_transient(self) -> bool {
+        match self {
+            ErrorKind::Network | ErrorKind::Timeout | ErrorKind::Server => true,
+            ErrorKind::Validation | ErrorKind::Decode => false,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ErrorKind::Network => "network error",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::Validation => "invalid request",
+            ErrorKind::Server => "server error",
+            ErrorKind::Decode => "unexpected response",
+        })
+    }
+}
+
+// ------ ErrorMessage ------
+
+#[derive(Shrinkwrap, Clone, Debug, Serialize, Deserialize)]
+pub struct ErrorMessage {
+    #[shrinkwrap(main_field)]
+    message: Cow<'static, str>,
+    kind: ErrorKind,
+    /// HTTP status of the response, if there was one.
+    status: Option<u16>,
+}
+
+impl ErrorMessage {
+    pub fn new(kind: ErrorKind, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: message.into(),
+            kind,
+            status: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// The request needs a logged in user, or the session has expired.
+    pub fn is_unauthorized(&self) -> bool {
+        self.status == Some(401)
+    }
+
+    pub fn into_inner(self) -> Cow<'static, str> {
+        self.message
+    }
+}
+
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Part of the URLs of the group, unique.
+pub type GroupSlug = String;
+
+/// A team with its own roster, rounds and talks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub slug: GroupSlug,
+    pub name: String,
+}
@@ -0,0 +1,20 @@
+pub mod employee;
+pub use employee::{Employee, EmployeeId};
+
+pub mod error_message;
+pub use error_message::{ErrorKind, ErrorMessage};
+
+pub mod talk;
+pub use talk::{Talk, TalkId, TalkStatus};
+
+pub mod round;
+pub use round::Round;
+
+pub mod absence;
+pub use absence::{Absence, AbsenceId};
+
+pub mod group;
+pub use group::{Group, GroupSlug};
+
+pub mod user;
+pub use user::{Role, User};
@@ -0,0 +1,13 @@
+use super::EmployeeId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A round ends once everybody has spoken and an organiser starts the next one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Round {
+    pub number: u32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// In the order they were picked.
+    pub picked: Vec<EmployeeId>,
+}
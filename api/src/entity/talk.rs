@@ -0,0 +1,38 @@
+use super::EmployeeId;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub type TalkId = Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TalkStatus {
+    /// The speaker has been picked, the talk isn't confirmed yet.
+    Pending,
+    Confirmed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Talk {
+    pub uuid: TalkId,
+    pub speaker_id: EmployeeId,
+    pub date: NaiveDate,
+    pub title: String,
+    pub slides: Option<String>,
+    pub status: TalkStatus,
+}
+
+impl Talk {
+    /// The slides, if they can safely be linked to.
+    pub fn slides_link(&self) -> Option<&str> {
+        self.slides.as_deref().filter(|slides| is_web_link(slides))
+    }
+}
+
+/// Whether `url` is an `http` or `https` URL, the only ones a link is made of:
+/// a `javascript:` URL would run in the page of whoever clicks it.
+pub fn is_web_link(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    url.starts_with("http://") || url.starts_with("https://")
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// What a user may do, each role may do what the previous ones may.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Role {
+    /// Sees the rounds and the talks.
+    Viewer,
+    /// Picks the speakers and manages their talks.
+    Organiser,
+    /// Manages the groups, the rosters, the absences and the rounds.
+    Admin,
+}
+
+impl Role {
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Viewer => "Viewer",
+            Role::Organiser => "Organiser",
+            Role::Admin => "Admin",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub name: String,
+    pub role: Role,
+}
+
+impl User {
+    /// Whether the user has the `role` or a higher one.
+    pub fn can(&self, role: Role) -> bool {
+        self.role >= role
+    }
+}
+
+/// Whether the `user`, `None` when nobody is logged in, may do what needs the `role`,
+/// `None` when anybody may.
+pub fn is_allowed(user: Option<&User>, role: Option<Role>) -> bool {
+    role.is_none_or(|role| user.is_some_and(|user| user.can(role)))
+}
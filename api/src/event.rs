@@ -0,0 +1,25 @@
+//! Changes pushed by the server to the open pages of a group, as Server-Sent Events.
+
+use crate::endpoint::{Picked, RoundState, TalkHistory};
+use crate::entity::{Absence, Employee};
+use serde::{Deserialize, Serialize};
+
+/// Path of the event stream, under the path of the group (see `endpoint::group_path`).
+///
+/// `EventSource` can't send headers, so the token goes in the `token` query parameter.
+pub const PATH: &str = "events";
+
+/// Sent as the `data` of an unnamed Server-Sent Event, in JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum Event {
+    /// Employees added, imported, updated or removed.
+    RosterChanged(Vec<Employee>),
+    /// A pick has been accepted.
+    Picked(Picked),
+    RoundStarted(RoundState),
+    /// A talk has been confirmed, rescheduled or cancelled.
+    TalksChanged(TalkHistory),
+    /// An absence has been declared or withdrawn.
+    AbsencesChanged(Vec<Absence>),
+}
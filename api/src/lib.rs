@@ -0,0 +1,6 @@
+//! Contracts shared by the Caliaconf front-end and the API server.
+
+pub mod endpoint;
+pub mod entity;
+pub mod event;
+pub mod pick;
@@ -0,0 +1,243 @@
+use crate::entity::Employee;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// ------ PickStrategy ------
+
+/// How the next speaker is chosen among the employees who haven't been picked yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PickStrategy {
+    /// Everybody has the same chance.
+    #[default]
+    Uniform,
+    /// The longer since someone's last talk, the higher their chance.
+    LeastRecent,
+    /// In the order employees joined.
+    RoundRobin,
+    /// Uniform, but reproducible for a given seed.
+    Seeded(u64),
+}
+
+impl PickStrategy {
+    pub const ALL: [PickStrategy; 4] = [
+        PickStrategy::Uniform,
+        PickStrategy::LeastRecent,
+        PickStrategy::RoundRobin,
+        PickStrategy::Seeded(0),
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PickStrategy::Uniform => "Random",
+            PickStrategy::LeastRecent => "Weighted by time since last talk",
+            PickStrategy::RoundRobin => "By join date",
+            PickStrategy::Seeded(_) => "Deterministic (seeded)",
+        }
+    }
+
+    /// Chooses among the `candidates`.
+    ///
+    /// `random` is a random number, it's ignored by deterministic strategies.
+    /// `already_picked` is the number of employees picked in the current cycle,
+    /// so a seeded pick yields a different but reproducible employee each time.
+    pub fn choose<'a>(
+        self,
+        candidates: &[&'a Employee],
+        now: DateTime<Utc>,
+        random: u64,
+        already_picked: usize,
+    ) -> Option<&'a Employee> {
+        if candidates.is_empty() {
+            return None;
+        }
+        match self {
+            PickStrategy::Uniform => candidates.get(index(random, candidates.len())).copied(),
+            PickStrategy::LeastRecent => choose_weighted(candidates, now, random),
+            PickStrategy::RoundRobin => candidates
+                .iter()
+                .min_by(|a, b| {
+                    // employees who joined before it was recorded come first
+                    (a.joined_at, &a.name).cmp(&(b.joined_at, &b.name))
+                })
+                .copied(),
+            PickStrategy::Seeded(seed) => {
+                let random = splitmix64(seed.wrapping_add(already_picked as u64));
+                let mut sorted = candidates.to_vec();
+                sorted.sort_by_key(|employee| employee.uuid);
+                sorted.get(index(random, sorted.len())).copied()
+            }
+        }
+    }
+}
+
+// ====== PRIVATE ======
+
+#[allow(clippy::cast_possible_truncation)]
+fn index(random: u64, len: usize) -> usize {
+    (random % len as u64) as usize
+}
+
+/// Weight: seconds since the last talk, or since joining for those who have never spoken.
+fn choose_weighted<'a>(
+    candidates: &[&'a Employee],
+    now: DateTime<Utc>,
+    random: u64,
+) -> Option<&'a Employee> {
+    let oldest = candidates
+        .iter()
+        .filter_map(|employee| employee.last_picked_at.or(employee.joined_at))
+        .min()
+        .unwrap_or(now);
+
+    let weights = candidates
+        .iter()
+        .map(|employee| {
+            let since = employee
+                .last_picked_at
+                .or(employee.joined_at)
+                .unwrap_or(oldest);
+            // `+ 1` so that someone who just joined still has a chance
+            (now - since).num_seconds().max(0) as u64 + 1
+        })
+        .collect::<Vec<_>>();
+
+    let total = weights.iter().sum::<u64>();
+    let mut target = random % total;
+    candidates
+        .iter()
+        .zip(weights)
+        .find(|(_, weight)| {
+            if target < *weight {
+                true
+            } else {
+                target -= weight;
+                false
+            }
+        })
+        .map(|(employee, _)| *employee)
+}
+
+/// http://xoshiro.di.unimi.it/splitmix64.c
+const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use uuid::Uuid;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2020, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn employee(id: u128, name: &str, joined_days_ago: Option<i64>) -> Employee {
+        Employee {
+            uuid: Uuid::from_u128(id),
+            name: name.to_owned(),
+            picked: false,
+            inactive: false,
+            joined_at: joined_days_ago.map(|days| now() - Duration::days(days)),
+            last_picked_at: None,
+        }
+    }
+
+    #[test]
+    fn no_candidates_picks_nobody() {
+        for strategy in PickStrategy::ALL.iter() {
+            assert!(strategy.choose(&[], now(), 42, 0).is_none());
+        }
+    }
+
+    #[test]
+    fn seeded_pick_is_reproducible() {
+        let employees = [
+            employee(1, "Ada", Some(10)),
+            employee(2, "Brian", Some(20)),
+            employee(3, "Chloe", Some(30)),
+            employee(4, "Dennis", Some(40)),
+        ];
+        let candidates = employees.iter().collect::<Vec<_>>();
+        let mut reversed = candidates.clone();
+        reversed.reverse();
+
+        for already_picked in 0..8 {
+            let pick = |candidates: &[&Employee], random| {
+                PickStrategy::Seeded(7)
+                    .choose(candidates, now(), random, already_picked)
+                    .map(|employee| employee.uuid)
+            };
+            // Neither the random number nor the order of the candidates matter.
+            assert_eq!(pick(&candidates, 1), pick(&candidates, 2));
+            assert_eq!(pick(&candidates, 1), pick(&reversed, 3));
+        }
+
+        let sequence = |seed| {
+            (0..8)
+                .map(|already_picked| {
+                    PickStrategy::Seeded(seed)
+                        .choose(&candidates, now(), 0, already_picked)
+                        .map(|employee| employee.uuid)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(7), sequence(7));
+        assert_ne!(sequence(7), sequence(8));
+    }
+
+    #[test]
+    fn least_recent_favours_those_who_spoke_long_ago() {
+        let mut recent = employee(1, "Recent", Some(400));
+        recent.last_picked_at = Some(now() - Duration::days(1));
+        let mut long_ago = employee(2, "Long ago", Some(400));
+        long_ago.last_picked_at = Some(now() - Duration::days(99));
+        let candidates = vec![&recent, &long_ago];
+
+        // Weights of 1 and 99 days, plus a second each: `random` falls in one or the other.
+        let recent_weight = (Duration::days(1).num_seconds() + 1) as u64;
+        let total = recent_weight + (Duration::days(99).num_seconds() + 1) as u64;
+        let pick = |random| {
+            PickStrategy::LeastRecent
+                .choose(&candidates, now(), random, 0)
+                .map(|employee| employee.name.as_str())
+        };
+        assert_eq!(pick(0), Some("Recent"));
+        assert_eq!(pick(recent_weight - 1), Some("Recent"));
+        assert_eq!(pick(recent_weight), Some("Long ago"));
+        assert_eq!(pick(total - 1), Some("Long ago"));
+        assert_eq!(pick(total), Some("Recent"));
+    }
+
+    #[test]
+    fn least_recent_never_excludes_someone_who_just_joined() {
+        let newcomer = employee(1, "Newcomer", Some(0));
+        let veteran = employee(2, "Veteran", Some(100));
+        let candidates = vec![&newcomer, &veteran];
+        assert_eq!(
+            PickStrategy::LeastRecent
+                .choose(&candidates, now(), 0, 0)
+                .map(|employee| employee.name.as_str()),
+            Some("Newcomer")
+        );
+    }
+
+    #[test]
+    fn round_robin_follows_the_join_order() {
+        let unknown = employee(1, "Unknown", None);
+        let first = employee(2, "First", Some(30));
+        let second = employee(3, "Second", Some(20));
+        let third = employee(4, "Third", Some(10));
+        let mut candidates = vec![&third, &first, &second, &unknown];
+
+        let mut order = Vec::new();
+        while let Some(picked) = PickStrategy::RoundRobin.choose(&candidates, now(), 99, 0) {
+            order.push(picked.name.as_str());
+            candidates.retain(|employee| employee.uuid != picked.uuid);
+        }
+        assert_eq!(order, vec!["Unknown", "First", "Second", "Third"]);
+    }
+}
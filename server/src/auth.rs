@@ -0,0 +1,77 @@
+//! Mock identity provider, for development: the users are listed in `CALIACONF_USERS`
+//! and their sessions only last as long as the server.
+
+use caliaconf_api::endpoint::{Credentials, Session};
+use caliaconf_api::entity::{Role, User};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Used when `CALIACONF_USERS` is not set.
+const DEFAULT_USERS: &str = "admin:admin:admin,organiser:organiser:organiser,viewer:viewer:viewer";
+
+pub struct Auth {
+    /// Password and user, by name.
+    users: HashMap<String, (String, User)>,
+    /// User, by token.
+    sessions: HashMap<String, User>,
+}
+
+impl Auth {
+    /// Reads the users from `name:password:role` entries separated by commas,
+    /// the role being `viewer`, `organiser` or `admin`.
+    pub fn new(users: Option<&str>) -> Result<Self, String> {
+        let users = users.unwrap_or_else(|| {
+            println!("CALIACONF_USERS is not set, using the development users");
+            DEFAULT_USERS
+        });
+        let users = users
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(parse_user)
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(Self {
+            users,
+            sessions: HashMap::new(),
+        })
+    }
+
+    /// Opens a session, if the credentials match a user.
+    pub fn login(&mut self, credentials: &Credentials) -> Option<Session> {
+        let (password, user) = self.users.get(credentials.name.trim())?;
+        if *password != credentials.password {
+            return None;
+        }
+        let token = Uuid::new_v4().to_string();
+        self.sessions.insert(token.clone(), user.clone());
+        Some(Session {
+            token,
+            user: user.clone(),
+        })
+    }
+
+    /// User of the session `token`.
+    pub fn user(&self, token: &str) -> Option<&User> {
+        self.sessions.get(token)
+    }
+}
+
+fn parse_user(entry: &str) -> Result<(String, (String, User)), String> {
+    let invalid = || format!("invalid user {:?}, expected name:password:role", entry);
+    let mut parts = entry.splitn(3, ':');
+    let (name, password, role) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(name), Some(password), Some(role)) if !name.is_empty() => (name, password, role),
+        _ => return Err(invalid()),
+    };
+    let role = match role {
+        "viewer" => Role::Viewer,
+        "organiser" => Role::Organiser,
+        "admin" => Role::Admin,
+        _ => return Err(invalid()),
+    };
+    let user = User {
+        name: name.to_owned(),
+        role,
+    };
+    Ok((name.to_owned(), (password.to_owned(), user)))
+}
@@ -0,0 +1,68 @@
+//! Server-Sent Events streams of the open pages, kept open to push the changes of their group.
+
+use caliaconf_api::event::Event;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::thread;
+use std::time::Duration;
+use tiny_http::Request;
+
+/// Events waiting for a slow stream, which is dropped when it falls further behind.
+const BACKLOG: usize = 32;
+/// Idle time after which a comment is sent to keep proxies from closing the stream.
+const KEEPALIVE: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct Events {
+    streams: Vec<Stream>,
+}
+
+/// A stream written by its own thread, so a stalled browser never holds up the requests.
+struct Stream {
+    group: String,
+    sender: SyncSender<String>,
+}
+
+impl Events {
+    /// Answers the `request` with an event stream of the `group`, left open.
+    pub fn subscribe(&mut self, group: &str, request: Request) -> io::Result<()> {
+        let mut writer = request.into_writer();
+        write!(
+            writer,
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Cache-Control: no-cache\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             \r\n"
+        )?;
+        writer.flush()?;
+        let (sender, receiver) = mpsc::sync_channel::<String>(BACKLOG);
+        thread::spawn(move || loop {
+            let written = match receiver.recv_timeout(KEEPALIVE) {
+                Ok(message) => writer.write_all(message.as_bytes()),
+                Err(RecvTimeoutError::Timeout) => writer.write_all(b": keepalive\n\n"),
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            if written.and_then(|()| writer.flush()).is_err() {
+                return;
+            }
+        });
+        self.streams.push(Stream {
+            group: group.to_owned(),
+            sender,
+        });
+        Ok(())
+    }
+
+    /// Sends the event made of `data` to the streams of the `group`, and gives `data` back.
+    ///
+    /// Streams closed by the browser, or too far behind, are dropped.
+    pub fn publish<T: Clone>(&mut self, group: &str, event: fn(T) -> Event, data: T) -> T {
+        let json = serde_json::to_string(&event(data.clone())).expect("serializable event");
+        let message = format!("data: {}\n\n", json);
+        self.streams.retain(|stream| {
+            stream.group != group || stream.sender.try_send(message.clone()).is_ok()
+        });
+        data
+    }
+}
@@ -0,0 +1,397 @@
+use crate::auth::Auth;
+use crate::events::Events;
+use crate::store::{self, Data, Store};
+use caliaconf_api::endpoint::{
+    self, AcceptPick, AddEmployee, AddGroup, CancelTalk, ConfirmTalk, Credentials, DeclareAbsence,
+    DrawEmployee, Endpoint, ErrorBody, ImportEmployees, RemoveEmployee, RescheduleTalk, RoundState,
+    TalkHistory, UpdateEmployee, WithdrawAbsence,
+};
+use caliaconf_api::entity::User;
+use caliaconf_api::event::{self, Event};
+use serde::Serialize;
+use std::io::{self, Cursor};
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+/// Handler result: the response of the endpoint, or a status code with the endpoint error.
+type Outcome<E> = Result<<E as Endpoint>::Response, (u16, <E as Endpoint>::Error)>;
+
+/// What a handler needs from the request, besides its path.
+#[derive(Clone, Copy)]
+struct Call<'a> {
+    method: &'a Method,
+    body: &'a str,
+    /// Logged in user, if the request has a valid token.
+    user: Option<&'a User>,
+}
+
+pub fn handle(
+    mut request: Request,
+    store: &mut Store,
+    auth: &mut Auth,
+    events: &mut Events,
+) -> io::Result<()> {
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body)?;
+
+    let url = request.url().trim_start_matches('/').to_owned();
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path.to_owned(), query.to_owned()),
+        None => (url, String::new()),
+    };
+    if let (Method::Get, Some((slug, event::PATH))) =
+        (request.method(), endpoint::split_group_path(&path))
+    {
+        return subscribe(request, slug, &query, store, auth, events);
+    }
+
+    let token = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+        .map(str::to_owned);
+    let response = match request.method() {
+        Method::Options => Response::from_data(Vec::new()),
+        method if path == endpoint::Login::PATH => login(method, &body, auth),
+        method => {
+            let call = Call {
+                method,
+                body: &body,
+                user: token.as_deref().and_then(|token| auth.user(token)),
+            };
+            route(call, &path, store, events)
+        }
+    };
+
+    request.respond(with_cors(response))
+}
+
+// ====== PRIVATE ======
+
+fn login(method: &Method, body: &str, auth: &mut Auth) -> HttpResponse {
+    let call = Call {
+        method,
+        body,
+        user: None,
+    };
+    serve::<endpoint::Login>(call, |credentials: Credentials| {
+        auth.login(&credentials)
+            .ok_or_else(|| (401, ErrorBody::new("Wrong name or password")))
+    })
+}
+
+/// Opens an event stream of the group `slug` for a user logged in with the `token` query parameter.
+fn subscribe(
+    request: Request,
+    slug: &str,
+    query: &str,
+    store: &Store,
+    auth: &Auth,
+    events: &mut Events,
+) -> io::Result<()> {
+    let token = query
+        .split('&')
+        .find_map(|parameter| parameter.strip_prefix("token="));
+    let response = match token.and_then(|token| auth.user(token)) {
+        None => json(401, &ErrorBody::new("Login required")),
+        Some(_) if store.group(slug).is_err() => json(404, &ErrorBody::new("Group not found")),
+        Some(_) => return events.subscribe(slug, request),
+    };
+    request.respond(with_cors(response))
+}
+
+fn route(call: Call, path: &str, store: &mut Store, events: &mut Events) -> HttpResponse {
+    match path {
+        endpoint::Groups::PATH => serve::<endpoint::Groups>(call, |()| Ok(store.groups())),
+        endpoint::CreateGroup::PATH => serve::<endpoint::CreateGroup>(call, |AddGroup { name }| {
+            store.add_group(&name).map_err(store_error)?;
+            Ok(store.groups())
+        }),
+        _ => match endpoint::split_group_path(path) {
+            Some((slug, path)) if store.group(slug).is_ok() => {
+                route_group(call, path, slug, store, events)
+            }
+            Some(_) => json(404, &ErrorBody::new("Group not found")),
+            None => json(404, &ErrorBody::new("Not found")),
+        },
+    }
+}
+
+/// Endpoints scoped to the group `slug`, which exists.
+fn route_group(
+    call: Call,
+    path: &str,
+    slug: &str,
+    store: &mut Store,
+    events: &mut Events,
+) -> HttpResponse {
+    match path {
+        endpoint::List::PATH => serve::<endpoint::List>(call, |()| {
+            read(store, slug, |data| data.employees().to_vec())
+        }),
+        endpoint::Add::PATH => serve::<endpoint::Add>(call, |AddEmployee { name }| {
+            change(store, slug, |data| {
+                data.add(name)?;
+                Ok(data.employees().to_vec())
+            })
+            .map(|employees| events.publish(slug, Event::RosterChanged, employees))
+        }),
+        endpoint::Import::PATH => serve::<endpoint::Import>(call, |ImportEmployees { names }| {
+            let names = names
+                .iter()
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<_>>();
+            if names.is_empty() {
+                return Err(invalid("Nobody to import"));
+            }
+            change(store, slug, |data| {
+                data.add_all(names)?;
+                Ok(data.employees().to_vec())
+            })
+            .map(|employees| events.publish(slug, Event::RosterChanged, employees))
+        }),
+        endpoint::Remove::PATH => {
+            serve::<endpoint::Remove>(call, |RemoveEmployee { employee_id }| {
+                change(store, slug, |data| {
+                    data.remove(employee_id)?;
+                    Ok(data.employees().to_vec())
+                })
+                .map(|employees| events.publish(slug, Event::RosterChanged, employees))
+            })
+        }
+        endpoint::Update::PATH => serve::<endpoint::Update>(
+            call,
+            |UpdateEmployee {
+                 employee_id,
+                 name,
+                 inactive,
+                 picked,
+             }| {
+                change(store, slug, |data| {
+                    data.update(employee_id, name, inactive, picked)?;
+                    Ok(data.employees().to_vec())
+                })
+                .map(|employees| events.publish(slug, Event::RosterChanged, employees))
+            },
+        ),
+        endpoint::Draw::PATH => {
+            serve::<endpoint::Draw>(call, |DrawEmployee { strategy, excluded }| {
+                read(store, slug, |data| data.draw(strategy, &excluded))
+            })
+        }
+        endpoint::Accept::PATH => serve::<endpoint::Accept>(call, |AcceptPick { employee_id }| {
+            change(store, slug, |data| {
+                let picked = data.accept(employee_id)?;
+                Ok((data.employees().to_vec(), picked))
+            })
+            .map(|picked| events.publish(slug, Event::Picked, picked))
+        }),
+        endpoint::Talks::PATH => {
+            serve::<endpoint::Talks>(call, |()| read(store, slug, talk_history))
+        }
+        endpoint::Confirm::PATH => serve::<endpoint::Confirm>(
+            call,
+            |ConfirmTalk {
+                 talk_id,
+                 title,
+                 slides,
+             }| {
+                change(store, slug, |data| {
+                    data.confirm_talk(talk_id, title, slides)?;
+                    Ok(talk_history(data))
+                })
+                .map(|history| events.publish(slug, Event::TalksChanged, history))
+            },
+        ),
+        endpoint::Reschedule::PATH => {
+            serve::<endpoint::Reschedule>(call, |RescheduleTalk { talk_id, date }| {
+                change(store, slug, |data| {
+                    data.reschedule_talk(talk_id, date)?;
+                    Ok(talk_history(data))
+                })
+                .map(|history| events.publish(slug, Event::TalksChanged, history))
+            })
+        }
+        endpoint::Cancel::PATH => serve::<endpoint::Cancel>(call, |CancelTalk { talk_id }| {
+            change(store, slug, |data| {
+                data.cancel_talk(talk_id)?;
+                Ok(talk_history(data))
+            })
+            .map(|history| events.publish(slug, Event::TalksChanged, history))
+        }),
+        endpoint::CurrentRound::PATH => {
+            serve::<endpoint::CurrentRound>(call, |()| read(store, slug, round_state))
+        }
+        endpoint::NewRound::PATH => serve::<endpoint::NewRound>(call, |()| {
+            change(store, slug, |data| {
+                data.new_round()?;
+                Ok(round_state(data))
+            })
+            .map(|round| events.publish(slug, Event::RoundStarted, round))
+        }),
+        endpoint::Absences::PATH => {
+            serve::<endpoint::Absences>(call, |()| read(store, slug, Data::absences))
+        }
+        endpoint::Declare::PATH => serve::<endpoint::Declare>(
+            call,
+            |DeclareAbsence {
+                 employee_id,
+                 from,
+                 to,
+                 reason,
+             }| {
+                change(store, slug, |data| {
+                    data.declare_absence(employee_id, from, to, reason)?;
+                    Ok(data.absences())
+                })
+                .map(|absences| events.publish(slug, Event::AbsencesChanged, absences))
+            },
+        ),
+        endpoint::Withdraw::PATH => {
+            serve::<endpoint::Withdraw>(call, |WithdrawAbsence { absence_id }| {
+                change(store, slug, |data| {
+                    data.withdraw_absence(absence_id)?;
+                    Ok(data.absences())
+                })
+                .map(|absences| events.publish(slug, Event::AbsencesChanged, absences))
+            })
+        }
+        _ => json(404, &ErrorBody::new("Not found")),
+    }
+}
+
+/// Checks the role of the user, decodes the request body of the endpoint `E`,
+/// runs `handler` and encodes its outcome.
+fn serve<E: Endpoint>(call: Call, handler: impl FnOnce(E::Request) -> Outcome<E>) -> HttpResponse {
+    if call.method.as_str() != E::METHOD.as_str() {
+        return json(405, &ErrorBody::new("Method not allowed"));
+    }
+
+    match (E::ROLE, call.user) {
+        (None, _) => {}
+        (Some(_), None) => return json(401, &ErrorBody::new("Login required")),
+        (Some(role), Some(user)) if !user.can(role) => {
+            let message = format!("Only the {} role may do that", role.label().to_lowercase());
+            return json(403, &ErrorBody::new(message));
+        }
+        (Some(_), Some(_)) => {}
+    }
+
+    let payload = match E::METHOD {
+        endpoint::Method::Get => serde_json::from_str("null"),
+        endpoint::Method::Post => serde_json::from_str(call.body),
+    };
+
+    match payload.map(handler) {
+        Ok(Ok(response)) => json(200, &response),
+        Ok(Err((status, error))) => json(status, &error),
+        Err(_) => json(400, &ErrorBody::new("Invalid payload")),
+    }
+}
+
+fn invalid(message: &str) -> (u16, ErrorBody) {
+    (400, ErrorBody::new(message))
+}
+
+fn store_error(error: store::Error) -> (u16, ErrorBody) {
+    match error {
+        store::Error::Invalid(message) => invalid(message),
+        store::Error::Io(error) => {
+            eprintln!("Server error: {}", error);
+            (500, ErrorBody::new("Internal error"))
+        }
+    }
+}
+
+/// Reads the data of the group `slug` with `reader`.
+fn read<T>(
+    store: &Store,
+    slug: &str,
+    reader: impl FnOnce(&Data) -> T,
+) -> Result<T, (u16, ErrorBody)> {
+    store.group(slug).map(reader).map_err(store_error)
+}
+
+/// Changes the data of the group `slug` with `change`, which also builds the response.
+fn change<T>(
+    store: &mut Store,
+    slug: &str,
+    change: impl FnOnce(&mut Data) -> store::Result<T>,
+) -> Result<T, (u16, ErrorBody)> {
+    store.update_group(slug, change).map_err(store_error)
+}
+
+fn round_state(data: &Data) -> RoundState {
+    (data.employees().to_vec(), data.current_round().clone())
+}
+
+fn talk_history(data: &Data) -> TalkHistory {
+    (data.employees().to_vec(), data.talks())
+}
+
+fn json<T: Serialize + ?Sized>(status: u16, data: &T) -> HttpResponse {
+    let body = serde_json::to_vec(data).expect("serializable response");
+    Response::from_data(body)
+        .with_status_code(StatusCode(status))
+        .with_header(header("Content-Type", "application/json"))
+}
+
+fn with_cors(response: HttpResponse) -> HttpResponse {
+    response
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"))
+        .with_header(header(
+            "Access-Control-Allow-Headers",
+            "Content-Type, Authorization",
+        ))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("valid header")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use caliaconf_api::entity::Role;
+    use std::io::Read;
+    use uuid::Uuid;
+
+    #[test]
+    fn a_duplicate_name_is_a_validation_error() {
+        let path = std::env::temp_dir().join(format!("caliaconf-{}.json", Uuid::new_v4()));
+        let mut store = Store::open(&path).unwrap();
+        let admin = User {
+            name: "admin".to_owned(),
+            role: Role::Admin,
+        };
+        let add = |store: &mut Store, name: &str| {
+            let body = serde_json::to_string(&AddEmployee {
+                name: name.to_owned(),
+            })
+            .unwrap();
+            let call = Call {
+                method: &Method::Post,
+                body: &body,
+                user: Some(&admin),
+            };
+            let path = endpoint::group_path("default", endpoint::Add::PATH);
+            let response = route(call, &path, store, &mut Events::default());
+            let status = response.status_code().0;
+            let mut body = String::new();
+            response.into_reader().read_to_string(&mut body).unwrap();
+            (status, body)
+        };
+
+        assert_eq!(add(&mut store, "Ada").0, 200);
+        let (status, body) = add(&mut store, "ada");
+        assert_eq!(status, 400);
+        let error: ErrorBody = serde_json::from_str(&body).unwrap();
+        assert_eq!(error.errors, vec!["This name already exists"]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,34 @@
+use auth::Auth;
+use events::Events;
+use std::env;
+use store::Store;
+use tiny_http::Server;
+
+mod auth;
+mod events;
+mod handler;
+mod store;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8001";
+const DEFAULT_DATA_FILE: &str = "employees.json";
+
+fn main() {
+    let address = env::var("CALIACONF_ADDRESS").unwrap_or_else(|_| DEFAULT_ADDRESS.to_owned());
+    let data_file =
+        env::var("CALIACONF_DATA_FILE").unwrap_or_else(|_| DEFAULT_DATA_FILE.to_owned());
+
+    let mut store = Store::open(&data_file)
+        .unwrap_or_else(|error| panic!("cannot open data file {}: {:?}", data_file, error));
+    let mut auth = Auth::new(env::var("CALIACONF_USERS").ok().as_deref())
+        .unwrap_or_else(|error| panic!("cannot read CALIACONF_USERS: {}", error));
+    let mut events = Events::default();
+    let server = Server::http(&address)
+        .unwrap_or_else(|error| panic!("cannot listen on {}: {}", address, error));
+
+    println!("Caliaconf API listening on http://{}", address);
+    for request in server.incoming_requests() {
+        if let Err(error) = handler::handle(request, &mut store, &mut auth, &mut events) {
+            eprintln!("Server error: {}", error);
+        }
+    }
+}
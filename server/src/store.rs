@@ -0,0 +1,766 @@
+use caliaconf_api::entity::absence;
+use caliaconf_api::entity::talk::is_web_link;
+use caliaconf_api::entity::{
+    Absence, AbsenceId, Employee, EmployeeId, Group, GroupSlug, Round, Talk, TalkId, TalkStatus,
+};
+use caliaconf_api::pick::PickStrategy;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Version of the stored format, saved along the groups.
+const VERSION: u32 = 1;
+/// Fields of the data of a single group, the format before groups existed.
+const SINGLE_GROUP_FIELDS: [&str; 4] = ["employees", "talks", "rounds", "absences"];
+
+// ------ Error ------
+
+#[derive(Debug)]
+pub enum Error {
+    /// The request can't be applied to the current data.
+    Invalid(&'static str),
+    Io(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Io(error.into())
+    }
+}
+
+// ------ Data ------
+
+/// Everything the store persists for a group.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Data {
+    #[serde(default)]
+    employees: Vec<Employee>,
+    #[serde(default)]
+    talks: Vec<Talk>,
+    /// Oldest first, the last one is the current round.
+    #[serde(default)]
+    rounds: Vec<Round>,
+    #[serde(default)]
+    absences: Vec<Absence>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GroupData {
+    #[serde(flatten)]
+    group: Group,
+    #[serde(flatten)]
+    data: Data,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Groups {
+    /// Missing from the first files with groups, which have the same format as the version 1.
+    #[serde(default = "first_version")]
+    version: u32,
+    groups: Vec<GroupData>,
+}
+
+// ------ Store ------
+
+/// Data of all the groups persisted as a JSON file, rewritten after every mutation.
+pub struct Store {
+    path: PathBuf,
+    groups: Groups,
+}
+
+impl Store {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut groups = match fs::read(&path) {
+            Ok(content) => Groups::parse(&content)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                Groups::with_default_group(Data::default())
+            }
+            Err(error) => return Err(error.into()),
+        };
+        for group in &mut groups.groups {
+            group.data.start_first_round();
+        }
+        Ok(Self { path, groups })
+    }
+
+    pub fn groups(&self) -> Vec<Group> {
+        self.groups
+            .groups
+            .iter()
+            .map(|group| group.group.clone())
+            .collect()
+    }
+
+    /// The slug of the new group is made from its `name`.
+    pub fn add_group(&mut self, name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(Error::Invalid("Name is required"));
+        }
+        let slug = slugify(name);
+        if slug.is_empty() {
+            return Err(Error::Invalid("The name needs a latin letter or a digit"));
+        }
+        if self
+            .groups
+            .groups
+            .iter()
+            .any(|group| group.group.slug == slug)
+        {
+            return Err(Error::Invalid("This group already exists"));
+        }
+        let mut data = Data::default();
+        data.start_first_round();
+        self.groups.groups.push(GroupData {
+            group: Group {
+                slug,
+                name: name.trim().to_owned(),
+            },
+            data,
+        });
+        self.save()
+    }
+
+    pub fn group(&self, slug: &str) -> Result<&Data> {
+        self.groups
+            .groups
+            .iter()
+            .find(|group| group.group.slug == slug)
+            .map(|group| &group.data)
+            .ok_or(Error::Invalid("Group not found"))
+    }
+
+    /// Changes the data of a group with `change`, saved when it succeeds.
+    pub fn update_group<T>(
+        &mut self,
+        slug: &str,
+        change: impl FnOnce(&mut Data) -> Result<T>,
+    ) -> Result<T> {
+        let data = self
+            .groups
+            .groups
+            .iter_mut()
+            .find(|group| group.group.slug == slug)
+            .map(|group| &mut group.data)
+            .ok_or(Error::Invalid("Group not found"))?;
+        let outcome = change(data)?;
+        self.save()?;
+        Ok(outcome)
+    }
+
+    // ====== PRIVATE ======
+
+    fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_vec_pretty(&self.groups)?)?;
+        Ok(())
+    }
+}
+
+impl Groups {
+    /// The groups of a file written by any version of the server.
+    ///
+    /// The first versions persisted only the list of employees, the next ones the data of a
+    /// single group. Anything else is rejected rather than taken for an empty group, which
+    /// would be saved over the file at the first change.
+    fn parse(content: &[u8]) -> Result<Self> {
+        let groups = match serde_json::from_slice(content)? {
+            Value::Object(fields) if fields.contains_key("groups") => {
+                serde_json::from_value::<Self>(Value::Object(fields))?
+            }
+            Value::Object(fields)
+                if fields
+                    .keys()
+                    .all(|field| SINGLE_GROUP_FIELDS.contains(&field.as_str())) =>
+            {
+                Self::with_default_group(serde_json::from_value(Value::Object(fields))?)
+            }
+            employees @ Value::Array(_) => Self::with_default_group(Data {
+                employees: serde_json::from_value(employees)?,
+                ..Data::default()
+            }),
+            _ => return Err(Error::Invalid("Unknown data format")),
+        };
+        if groups.version > VERSION {
+            return Err(Error::Invalid(
+                "Data saved by a newer version of the server",
+            ));
+        }
+        Ok(groups)
+    }
+
+    fn with_default_group(data: Data) -> Self {
+        Self {
+            version: VERSION,
+            groups: vec![GroupData {
+                group: Group {
+                    slug: "default".to_owned(),
+                    name: "Default".to_owned(),
+                },
+                data,
+            }],
+        }
+    }
+}
+
+impl Data {
+    pub fn employees(&self) -> &[Employee] {
+        &self.employees
+    }
+
+    pub fn current_round(&self) -> &Round {
+        self.rounds.last().expect("there is always a current round")
+    }
+
+    /// Most recent first.
+    pub fn talks(&self) -> Vec<Talk> {
+        let mut talks = self.talks.clone();
+        talks.sort_by_key(|talk| Reverse(talk.date));
+        talks
+    }
+
+    /// Sorted by start date.
+    pub fn absences(&self) -> Vec<Absence> {
+        let mut absences = self.absences.clone();
+        absences.sort_by_key(|absence| absence.from);
+        absences
+    }
+
+    pub fn add(&mut self, name: String) -> Result<()> {
+        self.add_all(vec![name])
+    }
+
+    /// Adds everybody or nobody, names are trimmed, required and unique regardless of case.
+    pub fn add_all(&mut self, names: Vec<String>) -> Result<()> {
+        let names = names
+            .iter()
+            .map(|name| name.trim())
+            .map(|name| match name {
+                "" => Err(Error::Invalid("Name is required")),
+                name => Ok(name.to_owned()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (index, name) in names.iter().enumerate() {
+            let name = name.to_lowercase();
+            if self
+                .employees
+                .iter()
+                .map(|employee| &employee.name)
+                .chain(&names[..index])
+                .any(|other| other.to_lowercase() == name)
+            {
+                return Err(Error::Invalid("This name already exists"));
+            }
+        }
+        let now = Utc::now();
+        self.employees
+            .extend(names.into_iter().map(|name| Employee {
+                uuid: Uuid::new_v4(),
+                name,
+                picked: false,
+                inactive: false,
+                joined_at: Some(now),
+                last_picked_at: None,
+            }));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, employee_id: EmployeeId) -> Result<()> {
+        self.employees
+            .retain(|employee| employee.uuid != employee_id);
+        self.absences
+            .retain(|absence| absence.employee_id != employee_id);
+        Ok(())
+    }
+
+    /// Changes the name, the activity or the picked flag of an employee.
+    /// The picked flag is kept in sync with the current round, without scheduling any talk.
+    pub fn update(
+        &mut self,
+        employee_id: EmployeeId,
+        name: Option<String>,
+        inactive: Option<bool>,
+        picked: Option<bool>,
+    ) -> Result<()> {
+        let name = match name.as_deref().map(str::trim) {
+            Some("") => return Err(Error::Invalid("Name is required")),
+            name => name.map(str::to_owned),
+        };
+        let employee = self
+            .employees
+            .iter_mut()
+            .find(|employee| employee.uuid == employee_id)
+            .ok_or(Error::Invalid("Employee not found"))?;
+
+        if let Some(name) = name {
+            employee.name = name;
+        }
+        if let Some(inactive) = inactive {
+            employee.inactive = inactive;
+        }
+        if let Some(picked) = picked {
+            employee.picked = picked;
+            let round = self.current_round_mut();
+            round.picked.retain(|uuid| *uuid != employee_id);
+            if picked {
+                round.picked.push(employee_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws an employee among the active ones not picked yet in the current round,
+    /// except the `excluded` ones and the ones absent today, according to the `strategy`.
+    /// Nobody is drawn once everybody has spoken, until a new round starts.
+    pub fn draw(&self, strategy: PickStrategy, excluded: &[EmployeeId]) -> Option<Employee> {
+        let today = absence::today();
+        let (picked, candidates): (Vec<_>, Vec<_>) = self
+            .employees
+            .iter()
+            .filter(|employee| !employee.inactive && !excluded.contains(&employee.uuid))
+            .filter(|employee| employee.picked || !self.is_absent(employee.uuid, today))
+            .partition(|employee| employee.picked);
+        strategy
+            .choose(&candidates, Utc::now(), rand::random(), picked.len())
+            .cloned()
+    }
+
+    /// Marks the employee as picked in the current round
+    /// and schedules a pending talk for them today.
+    pub fn accept(&mut self, employee_id: EmployeeId) -> Result<Employee> {
+        let now = Utc::now();
+        if self.is_absent(employee_id, absence::today()) {
+            return Err(Error::Invalid("Absent today"));
+        }
+        let employee = self
+            .employees
+            .iter_mut()
+            .find(|employee| employee.uuid == employee_id)
+            .ok_or(Error::Invalid("Employee not found"))?;
+        if employee.inactive {
+            return Err(Error::Invalid("Inactive employees can't be picked"));
+        }
+        if employee.picked {
+            return Err(Error::Invalid("Already picked this round"));
+        }
+        employee.picked = true;
+        employee.last_picked_at = Some(now);
+        let employee = employee.clone();
+
+        self.current_round_mut().picked.push(employee.uuid);
+        self.talks.push(Talk {
+            uuid: Uuid::new_v4(),
+            speaker_id: employee.uuid,
+            date: now.date_naive(),
+            title: String::new(),
+            slides: None,
+            status: TalkStatus::Pending,
+        });
+        Ok(employee)
+    }
+
+    /// Ends the current round and starts the next one, where nobody has spoken yet.
+    pub fn new_round(&mut self) -> Result<()> {
+        let now = Utc::now();
+        let current_round = self.current_round_mut();
+        current_round.ended_at = Some(now);
+        let number = current_round.number + 1;
+
+        self.rounds.push(Round {
+            number,
+            started_at: now,
+            ended_at: None,
+            picked: Vec::new(),
+        });
+        for employee in &mut self.employees {
+            employee.picked = false;
+        }
+        Ok(())
+    }
+
+    pub fn declare_absence(
+        &mut self,
+        employee_id: EmployeeId,
+        from: NaiveDate,
+        to: NaiveDate,
+        reason: String,
+    ) -> Result<()> {
+        if to < from {
+            return Err(Error::Invalid("The absence must end after it starts"));
+        }
+        if self
+            .employees
+            .iter()
+            .all(|employee| employee.uuid != employee_id)
+        {
+            return Err(Error::Invalid("Employee not found"));
+        }
+        self.absences.push(Absence {
+            uuid: Uuid::new_v4(),
+            employee_id,
+            from,
+            to,
+            reason: reason.trim().to_owned(),
+        });
+        Ok(())
+    }
+
+    pub fn withdraw_absence(&mut self, absence_id: AbsenceId) -> Result<()> {
+        self.absences.retain(|absence| absence.uuid != absence_id);
+        Ok(())
+    }
+
+    pub fn confirm_talk(
+        &mut self,
+        talk_id: TalkId,
+        title: String,
+        slides: Option<String>,
+    ) -> Result<()> {
+        if title.trim().is_empty() {
+            return Err(Error::Invalid("Title is required"));
+        }
+        let slides = slides
+            .map(|slides| slides.trim().to_owned())
+            .filter(|slides| !slides.is_empty());
+        if slides.as_deref().is_some_and(|slides| !is_web_link(slides)) {
+            return Err(Error::Invalid("The slides must be an http or https link"));
+        }
+        let talk = self.pending_talk(talk_id)?;
+        talk.title = title.trim().to_owned();
+        talk.slides = slides;
+        talk.status = TalkStatus::Confirmed;
+        Ok(())
+    }
+
+    pub fn reschedule_talk(&mut self, talk_id: TalkId, date: NaiveDate) -> Result<()> {
+        self.pending_talk(talk_id)?.date = date;
+        Ok(())
+    }
+
+    /// The speaker of a cancelled talk can be picked again in the current round.
+    pub fn cancel_talk(&mut self, talk_id: TalkId) -> Result<()> {
+        let talk = self.pending_talk(talk_id)?;
+        talk.status = TalkStatus::Cancelled;
+        let speaker_id = talk.speaker_id;
+
+        if let Some(speaker) = self
+            .employees
+            .iter_mut()
+            .find(|employee| employee.uuid == speaker_id)
+        {
+            speaker.picked = false;
+        }
+        self.current_round_mut()
+            .picked
+            .retain(|employee_id| *employee_id != speaker_id);
+        Ok(())
+    }
+
+    // ====== PRIVATE ======
+
+    fn current_round_mut(&mut self) -> &mut Round {
+        self.rounds
+            .last_mut()
+            .expect("there is always a current round")
+    }
+
+    fn is_absent(&self, employee_id: EmployeeId, date: NaiveDate) -> bool {
+        self.absences
+            .iter()
+            .any(|absence| absence.employee_id == employee_id && absence.covers(date))
+    }
+
+    fn pending_talk(&mut self, talk_id: TalkId) -> Result<&mut Talk> {
+        let talk = self
+            .talks
+            .iter_mut()
+            .find(|talk| talk.uuid == talk_id)
+            .ok_or(Error::Invalid("Talk not found"))?;
+        if talk.status == TalkStatus::Pending {
+            Ok(talk)
+        } else {
+            Err(Error::Invalid("Only pending talks can be changed"))
+        }
+    }
+
+    /// Data saved before rounds existed: the ones already picked belong to the first round.
+    fn start_first_round(&mut self) {
+        if self.rounds.is_empty() {
+            let picked = self
+                .employees
+                .iter()
+                .filter(|employee| employee.picked)
+                .map(|employee| employee.uuid)
+                .collect();
+            self.rounds.push(Round {
+                number: 1,
+                started_at: Utc::now(),
+                ended_at: None,
+                picked,
+            });
+        }
+    }
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+/// Lowercase ASCII letters and digits of the `name`, words separated by dashes.
+///
+/// Slugs are part of the URLs, where other characters are percent-encoded:
+/// accented letters are stripped of their accent, other characters separate words.
+fn slugify(name: &str) -> GroupSlug {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(without_accent)
+        .collect::<String>()
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The letter of a lowercase accented latin `character`.
+fn without_accent(character: char) -> char {
+    match character {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        character => character,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn data(names: &[&str]) -> Data {
+        let mut data = Data::default();
+        data.start_first_round();
+        data.add_all(names.iter().map(|name| (*name).to_owned()).collect())
+            .unwrap();
+        data
+    }
+
+    fn uuid(data: &Data, name: &str) -> EmployeeId {
+        data.employees
+            .iter()
+            .find(|employee| employee.name == name)
+            .map(|employee| employee.uuid)
+            .unwrap()
+    }
+
+    /// A data file with the `content`, removed once the `test` has run.
+    fn with_file(content: Option<&str>, test: impl FnOnce(&Path)) {
+        let path = std::env::temp_dir().join(format!("caliaconf-{}.json", Uuid::new_v4()));
+        if let Some(content) = content {
+            fs::write(&path, content).unwrap();
+        }
+        test(&path);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn names_are_trimmed_and_required() {
+        let mut data = data(&[" Ada "]);
+        assert_eq!(data.employees[0].name, "Ada");
+        assert!(data.add("  ".to_owned()).is_err());
+        assert!(data
+            .add_all(vec!["Brian".to_owned(), String::new()])
+            .is_err());
+        assert_eq!(data.employees.len(), 1);
+    }
+
+    #[test]
+    fn names_are_unique_regardless_of_case() {
+        let mut data = data(&["Ada"]);
+        assert!(data.add("ADA ".to_owned()).is_err());
+        assert!(data
+            .add_all(vec!["Brian".to_owned(), "brian".to_owned()])
+            .is_err());
+        assert_eq!(data.employees.len(), 1);
+    }
+
+    #[test]
+    fn removing_an_employee_withdraws_their_absences() {
+        let mut data = data(&["Ada", "Brian"]);
+        let (ada, brian) = (uuid(&data, "Ada"), uuid(&data, "Brian"));
+        let today = absence::today();
+        data.declare_absence(ada, today, today, String::new())
+            .unwrap();
+        data.declare_absence(brian, today, today, String::new())
+            .unwrap();
+        data.remove(ada).unwrap();
+        assert_eq!(data.employees.len(), 1);
+        assert_eq!(data.absences.len(), 1);
+        assert_eq!(data.absences[0].employee_id, brian);
+    }
+
+    #[test]
+    fn only_active_people_not_picked_nor_absent_are_drawn() {
+        let mut data = data(&["Ada", "Brian", "Chloe", "Dan"]);
+        let (ada, brian, chloe, dan) = (
+            uuid(&data, "Ada"),
+            uuid(&data, "Brian"),
+            uuid(&data, "Chloe"),
+            uuid(&data, "Dan"),
+        );
+        data.update(ada, None, Some(true), None).unwrap();
+        data.accept(brian).unwrap();
+        let today = absence::today();
+        data.declare_absence(chloe, today, today, String::new())
+            .unwrap();
+        for _ in 0..10 {
+            assert_eq!(data.draw(PickStrategy::Uniform, &[]).unwrap().uuid, dan);
+        }
+        assert!(data.draw(PickStrategy::Uniform, &[dan]).is_none());
+    }
+
+    #[test]
+    fn accepting_records_the_pick_and_a_pending_talk() {
+        let mut data = data(&["Ada"]);
+        let ada = uuid(&data, "Ada");
+        let picked = data.accept(ada).unwrap();
+        assert!(picked.picked);
+        assert!(picked.last_picked_at.is_some());
+        assert_eq!(data.current_round().picked, vec![ada]);
+        assert_eq!(data.talks.len(), 1);
+        assert_eq!(data.talks[0].speaker_id, ada);
+        assert_eq!(data.talks[0].status, TalkStatus::Pending);
+        assert!(data.accept(ada).is_err());
+        assert!(data.draw(PickStrategy::Uniform, &[]).is_none());
+    }
+
+    #[test]
+    fn a_new_round_lets_everybody_be_picked_again() {
+        let mut data = data(&["Ada"]);
+        let ada = uuid(&data, "Ada");
+        data.accept(ada).unwrap();
+        data.new_round().unwrap();
+        assert_eq!(data.rounds.len(), 2);
+        assert!(data.rounds[0].ended_at.is_some());
+        assert_eq!(data.current_round().number, 2);
+        assert!(data.current_round().picked.is_empty());
+        assert!(!data.employees[0].picked);
+        assert_eq!(data.draw(PickStrategy::Uniform, &[]).unwrap().uuid, ada);
+    }
+
+    #[test]
+    fn a_missing_file_opens_an_empty_default_group() {
+        with_file(None, |path| {
+            let store = Store::open(path).unwrap();
+            assert_eq!(store.groups()[0].slug, "default");
+            assert!(store.group("default").unwrap().employees.is_empty());
+        });
+    }
+
+    #[test]
+    fn the_list_of_employees_of_the_first_versions_opens_as_the_default_group() {
+        let ada = Uuid::new_v4();
+        let content = format!(
+            r#"[{{"uuid": "{}", "name": "Ada", "picked": true}}, {{"uuid": "{}", "name": "Brian", "picked": false}}]"#,
+            ada,
+            Uuid::new_v4()
+        );
+        with_file(Some(&content), |path| {
+            let store = Store::open(path).unwrap();
+            let data = store.group("default").unwrap();
+            assert_eq!(data.employees.len(), 2);
+            assert_eq!(data.current_round().picked, vec![ada]);
+        });
+    }
+
+    #[test]
+    fn the_data_of_a_single_group_opens_as_the_default_group() {
+        let content = serde_json::to_string(&data(&["Ada"])).unwrap();
+        with_file(Some(&content), |path| {
+            let store = Store::open(path).unwrap();
+            let data = store.group("default").unwrap();
+            assert_eq!(data.employees[0].name, "Ada");
+            assert_eq!(data.rounds.len(), 1);
+        });
+    }
+
+    #[test]
+    fn saved_groups_open_as_they_were() {
+        with_file(None, |path| {
+            let mut store = Store::open(path).unwrap();
+            store.add_group("Ops").unwrap();
+            store
+                .update_group("ops", |data| data.add("Ada".to_owned()))
+                .unwrap();
+            let store = Store::open(path).unwrap();
+            assert_eq!(store.groups().len(), 2);
+            assert_eq!(store.group("ops").unwrap().employees[0].name, "Ada");
+        });
+    }
+
+    #[test]
+    fn slugs_are_made_of_ascii_letters_and_digits() {
+        assert_eq!(slugify("Équipe Noël"), "equipe-noel");
+        assert_eq!(slugify("Ops & Dev (2024)!"), "ops-dev-2024");
+        assert_eq!(slugify("  team -- ops__Łódź  "), "team-ops-lodz");
+        assert_eq!(slugify("チーム"), "");
+    }
+
+    #[test]
+    fn a_group_needs_a_name_with_a_latin_letter_or_a_digit() {
+        with_file(None, |path| {
+            let mut store = Store::open(path).unwrap();
+            for name in &["", "  ", "?!", "チーム"] {
+                assert!(store.add_group(name).is_err(), "{:?} was added", name);
+            }
+            store.add_group("Équipe Noël").unwrap();
+            assert!(store.add_group("equipe noel").is_err());
+            assert_eq!(store.groups()[1].slug, "equipe-noel");
+        });
+    }
+
+    #[test]
+    fn a_corrupt_file_is_rejected_and_left_as_is() {
+        let contents = [
+            r#"{"groups": [{"slug": "ops", "name": "Ops", "employees": "Ada"}]}"#,
+            r#"{"version": 2, "groups": []}"#,
+            r#"{"employees": [{"name": "Ada"}]}"#,
+            r#"{"grops": []}"#,
+            r#""Ada""#,
+            "{",
+        ];
+        for content in &contents {
+            with_file(Some(content), |path| {
+                assert!(Store::open(path).is_err(), "{} was opened", content);
+                assert_eq!(fs::read_to_string(path).unwrap(), *content);
+            });
+        }
+    }
+}
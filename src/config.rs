@@ -0,0 +1,52 @@
+/// The self-hosted server of the `server` crate, on its default address.
+const DEFAULT_API_URL: &str = "http://127.0.0.1:8001";
+const DEFAULT_TIMEOUT: u32 = 5000;
+
+const API_URL_META: &str = "caliaconf:api-url";
+const TIMEOUT_META: &str = "caliaconf:timeout";
+
+// ------ Config ------
+
+/// Runtime configuration, resolved once at startup.
+///
+/// Every value is looked up, in order, in a `<meta name="caliaconf:...">` tag of `index.html`,
+/// in the environment at build time (`CALIACONF_API_URL`, `CALIACONF_TIMEOUT`)
+/// and finally falls back to the defaults of the self-hosted server.
+/// The tags are shipped empty, so that the build-time environment applies until they are filled in.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub api_url: String,
+    pub timeout: u32,
+    /// Token of the logged in user, set on login rather than at startup.
+    pub token: Option<String>,
+}
+
+impl Config {
+    pub fn from_document() -> Self {
+        let api_url = meta_content(API_URL_META)
+            .or_else(|| option_env!("CALIACONF_API_URL").map(str::to_owned))
+            .unwrap_or_else(|| DEFAULT_API_URL.to_owned());
+
+        let timeout = meta_content(TIMEOUT_META)
+            .or_else(|| option_env!("CALIACONF_TIMEOUT").map(str::to_owned))
+            .and_then(|timeout| timeout.parse().ok())
+            .unwrap_or(DEFAULT_TIMEOUT);
+
+        Self {
+            api_url: api_url.trim_end_matches('/').to_owned(),
+            timeout,
+            token: None,
+        }
+    }
+}
+
+// ====== PRIVATE ======
+
+fn meta_content(name: &str) -> Option<String> {
+    seed::document()
+        .query_selector(&format!("meta[name='{}']", name))
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.get_attribute("content"))
+        .filter(|content| !content.trim().is_empty())
+}
@@ -1,5 +0,0 @@
-pub mod employee;
-pub use employee::{Employee, EmployeeId};
-
-pub mod error_message;
-pub use error_message::ErrorMessage;
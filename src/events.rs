@@ -0,0 +1,66 @@
+//! Changes made by other people, pushed by the server while a page of their group is open.
+
+use crate::config::Config;
+use crate::logger;
+use caliaconf_api::endpoint;
+use caliaconf_api::entity::GroupSlug;
+use caliaconf_api::event::{self, Event};
+use seed::prelude::*;
+use web_sys::{EventSource, MessageEvent};
+
+// ------ Subscription ------
+
+/// The event stream of a group, closed when dropped.
+///
+/// The browser reconnects by itself when the connection is lost.
+pub struct Subscription {
+    group: GroupSlug,
+    source: EventSource,
+    /// Called by the source, it must live as long as the source.
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl Subscription {
+    /// Calls `on_event` with every event of the `group`, for the user logged in `config`.
+    pub fn new(
+        config: &Config,
+        group: GroupSlug,
+        on_event: impl Fn(Event) + 'static,
+    ) -> Option<Self> {
+        let url = format!(
+            "{}/{}?token={}",
+            config.api_url,
+            endpoint::group_path(&group, event::PATH),
+            config.token.as_ref()?
+        );
+        let source = EventSource::new(&url).map_err(logger::error).ok()?;
+        let on_message = Closure::wrap(Box::new(move |message: MessageEvent| {
+            let event = message
+                .data()
+                .as_string()
+                .ok_or_else(|| "not a text message".to_owned())
+                .and_then(|data| serde_json::from_str(&data).map_err(|error| error.to_string()));
+            match event {
+                Ok(event) => on_event(event),
+                Err(error) => logger::error(error),
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+        source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        Some(Self {
+            group,
+            source,
+            _on_message: on_message,
+        })
+    }
+
+    pub fn group(&self) -> &GroupSlug {
+        &self.group
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
@@ -0,0 +1,38 @@
+use js_sys::{Array, Promise};
+use seed::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, BlobPropertyBag, File, FileReader, HtmlAnchorElement, Url};
+
+/// Reads the whole `file` as text, with a `FileReader`.
+pub async fn read_as_text(file: File) -> Result<String, JsValue> {
+    let reader = FileReader::new()?;
+    let loaded = Promise::new(&mut |resolve, reject| {
+        reader.set_onload(Some(&resolve));
+        reader.set_onerror(Some(&reject));
+    });
+    reader.read_as_text(&file)?;
+    JsFuture::from(loaded).await?;
+
+    reader
+        .result()?
+        .as_string()
+        .ok_or_else(|| JsValue::from_str("The file isn't a text file"))
+}
+
+/// Makes the browser download `content` as a file named `file_name`.
+pub fn download(file_name: &str, mime_type: &str, content: &str) -> Result<(), JsValue> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_str_sequence_and_options(
+        &Array::of1(&JsValue::from_str(content)),
+        &options,
+    )?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let link: HtmlAnchorElement = seed::document().create_element("a")?.unchecked_into();
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    Url::revoke_object_url(&url)
+}
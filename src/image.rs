@@ -0,0 +1,38 @@
+// ------ Image ------
+
+/// An SVG compiled into the bundle, so that it's shown even when the server can't be reached.
+pub struct Image {
+    svg: &'static str,
+}
+
+impl Image {
+    /// A spinner.
+    pub const fn loading() -> Self {
+        Self {
+            svg: include_str!("image/loading.svg"),
+        }
+    }
+
+    /// A broken window.
+    pub const fn error() -> Self {
+        Self {
+            svg: include_str!("image/error.svg"),
+        }
+    }
+
+    /// A `data:` URL, for the `src` of an `img`.
+    pub fn url(&self) -> String {
+        let mut url = String::from("data:image/svg+xml,");
+        for byte in self.svg.trim().bytes() {
+            match byte {
+                b'\n' | b'\r' => url.push(' '),
+                b'"' | b'#' | b'%' | b'<' | b'>' | b'&' | b'?' => {
+                    url.push_str(&format!("%{:02X}", byte));
+                }
+                _ if !byte.is_ascii() => url.push_str(&format!("%{:02X}", byte)),
+                _ => url.push(char::from(byte)),
+            }
+        }
+        url
+    }
+}
@@ -1,30 +1,67 @@
 use seed::prelude::*;
 use std::convert::TryInto;
+use std::future::Future;
 
+use caliaconf_api::endpoint::Session;
+use caliaconf_api::entity::{ErrorMessage, Group, GroupSlug};
+use caliaconf_api::event::Event;
+use caliaconf_api::pick::PickStrategy;
+use config::Config;
+use notification::{Notification, NotificationId, Notifications};
+use outbox::{Mutation, Outbox};
+use preferences::Preferences;
 pub use route::Route;
+use theme::Theme;
 
-mod entity;
+mod config;
+mod events;
+mod file;
+mod image;
 mod loading;
 mod logger;
+mod notification;
+mod outbox;
 mod page;
+mod preferences;
 mod request;
+mod roster;
 mod route;
+mod storage;
+mod theme;
+mod timer;
 
 // ------ ------
 //     Model
 // ------ ------
 
-enum Model {
+struct Model {
+    config: Config,
+    notifications: Notifications,
+    preferences: Preferences,
+    /// `None` until the user logs in.
+    session: Option<Session>,
+    /// Changes made offline, not sent yet.
+    outbox: Outbox,
+    groups: Vec<Group>,
+    /// The group of the current page.
+    group: Option<GroupSlug>,
+    /// Changes made to the `group` by other people.
+    subscription: Option<events::Subscription>,
+    page: PageModel,
+}
+
+#[derive(Default)]
+enum PageModel {
+    #[default]
     Redirect,
     NotFound,
+    Login(page::login::Model),
+    Groups(page::groups::Model),
     Home(page::home::Model),
+    Session(page::session::Model),
     Settings(page::settings::Model),
-}
-
-impl Default for Model {
-    fn default() -> Self {
-        Model::Redirect
-    }
+    History(page::history::Model),
+    Employee(page::employee::Model),
 }
 
 // ------ ------
@@ -42,9 +79,39 @@ fn before_mount(_: Url) -> BeforeMount {
 // ------ ------
 
 fn after_mount(url: Url, orders: &mut impl Orders<Msg, GMsg>) -> AfterMount<Model> {
-    orders.send_msg(Msg::RouteChanged(url.try_into().ok()));
+    let mut config = Config::from_document();
+    let preferences = storage::load_preferences();
+    preferences.theme.apply();
+    let session = storage::load_session();
+    config.token = session.as_ref().map(|session| session.token.clone());
+
+    let route = match (url.try_into().ok(), &preferences.group) {
+        // Back to the group visited last.
+        (Some(Route::Root), Some(group)) => {
+            let route = Route::Home(group.clone());
+            seed::push_route(route.clone());
+            Some(route)
+        }
+        (route, _) => route,
+    };
+    orders.send_msg(Msg::RouteChanged(route));
+    if session.is_some() {
+        orders
+            .send_msg(Msg::Online)
+            .perform_cmd(load_groups(&config));
+    }
 
-    let model = Model::Redirect;
+    let model = Model {
+        config,
+        notifications: Notifications::default(),
+        preferences,
+        session,
+        outbox: Outbox::load(),
+        groups: Vec::new(),
+        group: None,
+        subscription: None,
+        page: PageModel::default(),
+    };
     AfterMount::new(model).url_handling(UrlHandling::None)
 }
 
@@ -54,11 +121,70 @@ fn after_mount(url: Url, orders: &mut impl Orders<Msg, GMsg>) -> AfterMount<Mode
 
 pub enum GMsg {
     RoutePushed(Route),
+    Notify(Notification),
+    PickStrategyChanged(PickStrategy),
+    GroupsChanged(Vec<Group>),
+    /// Sends the mutation once the connection is back.
+    Queue(Mutation),
+    LoggedIn(Session),
+    /// The user logged out, or their session has expired.
+    LoggedOut,
 }
 
 fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
-    if let GMsg::RoutePushed(route) = g_msg {
-        orders.send_msg(Msg::RouteChanged(Some(route.clone())));
+    match g_msg {
+        GMsg::RoutePushed(route) => {
+            orders.send_msg(Msg::RouteChanged(Some(route)));
+        }
+        GMsg::Notify(notification) => {
+            let severity = notification.severity;
+            let id = model.notifications.push(notification);
+            orders.perform_cmd(notification::expire(
+                id,
+                severity,
+                Msg::NotificationDismissed,
+            ));
+        }
+        GMsg::PickStrategyChanged(pick_strategy) => {
+            model.preferences.pick_strategy = pick_strategy;
+            storage::save_preferences(&model.preferences);
+        }
+        GMsg::GroupsChanged(groups) => {
+            model.groups = groups;
+        }
+        GMsg::Queue(mutation) => {
+            let message = format!(
+                "You are offline, \"{}\" will be sent once the connection is back",
+                mutation.description()
+            );
+            model.outbox.push(mutation);
+            sink(GMsg::Notify(Notification::warning(message)), model, orders);
+        }
+        GMsg::LoggedIn(session) => {
+            storage::save_session(&session);
+            model.config.token = Some(session.token.clone());
+            model.session = Some(session);
+            orders
+                .send_msg(Msg::Online)
+                .perform_cmd(load_groups(&model.config));
+            let route = model
+                .preferences
+                .group
+                .clone()
+                .map_or(Route::Root, Route::Home);
+            route::go_to(route, orders);
+        }
+        GMsg::LoggedOut => {
+            if model.session.take().is_none() {
+                // Several requests of the same page may fail with a 401.
+                return;
+            }
+            storage::forget_session();
+            model.config.token = None;
+            model.subscription = None;
+            model.groups.clear();
+            route::go_to(Route::Login, orders);
+        }
     }
 }
 
@@ -69,8 +195,22 @@ fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
 #[allow(clippy::enum_variant_names)]
 enum Msg {
     RouteChanged(Option<Route>),
+    GroupsLoaded(Result<Vec<Group>, Vec<ErrorMessage>>),
+    GroupsMsg(page::groups::Msg),
     HomeMsg(page::home::Msg),
+    SessionMsg(page::session::Msg),
     SettingsMsg(page::settings::Msg),
+    HistoryMsg(page::history::Msg),
+    EmployeeMsg(page::employee::Msg),
+    LoginMsg(page::login::Msg),
+    NotificationDismissed(NotificationId),
+    ThemeChanged(Theme),
+    LogOut,
+    /// A change of the group made by someone else.
+    EventReceived(Event),
+    /// The connection is back.
+    Online,
+    MutationSent(Result<(), Vec<ErrorMessage>>),
 }
 
 fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
@@ -78,20 +218,198 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
         Msg::RouteChanged(route) => {
             change_model_by_route(route, model, orders);
         }
+        Msg::GroupsLoaded(Ok(groups)) => {
+            model.groups = groups;
+        }
+        Msg::GroupsLoaded(Err(errors)) => {
+            notification::notify_failure("Loading groups", &errors, orders);
+        }
+        Msg::GroupsMsg(module_msg) => {
+            if let PageModel::Groups(module_model) = &mut model.page {
+                page::groups::update(
+                    module_msg,
+                    module_model,
+                    &model.config,
+                    &mut orders.proxy(Msg::GroupsMsg),
+                );
+            }
+        }
         Msg::HomeMsg(module_msg) => {
-            if let Model::Home(module_model) = model {
-                page::home::update(module_msg, module_model, &mut orders.proxy(Msg::HomeMsg));
+            if let PageModel::Home(module_model) = &mut model.page {
+                page::home::update(
+                    module_msg,
+                    module_model,
+                    &model.config,
+                    &mut orders.proxy(Msg::HomeMsg),
+                );
+            }
+        }
+        Msg::SessionMsg(module_msg) => {
+            if let PageModel::Session(module_model) = &mut model.page {
+                page::session::update(
+                    module_msg,
+                    module_model,
+                    &model.config,
+                    &mut orders.proxy(Msg::SessionMsg),
+                );
             }
         }
         Msg::SettingsMsg(module_msg) => {
-            if let Model::Settings(module_model) = model {
+            if let PageModel::Settings(module_model) = &mut model.page {
                 page::settings::update(
                     module_msg,
                     module_model,
+                    &model.config,
                     &mut orders.proxy(Msg::SettingsMsg),
                 );
             }
         }
+        Msg::HistoryMsg(module_msg) => {
+            if let PageModel::History(module_model) = &mut model.page {
+                page::history::update(
+                    module_msg,
+                    module_model,
+                    &model.config,
+                    &mut orders.proxy(Msg::HistoryMsg),
+                );
+            }
+        }
+        Msg::EmployeeMsg(module_msg) => {
+            if let PageModel::Employee(module_model) = &mut model.page {
+                page::employee::update(
+                    module_msg,
+                    module_model,
+                    &model.config,
+                    &mut orders.proxy(Msg::EmployeeMsg),
+                );
+            }
+        }
+        Msg::LoginMsg(module_msg) => {
+            if let PageModel::Login(module_model) = &mut model.page {
+                page::login::update(
+                    module_msg,
+                    module_model,
+                    &model.config,
+                    &mut orders.proxy(Msg::LoginMsg),
+                );
+            }
+        }
+        Msg::NotificationDismissed(id) => {
+            model.notifications.dismiss(id);
+        }
+        Msg::ThemeChanged(theme) => {
+            theme.apply();
+            model.preferences.theme = theme;
+            storage::save_preferences(&model.preferences);
+        }
+        Msg::EventReceived(event) => match &mut model.page {
+            PageModel::Home(module_model) => page::home::update(
+                page::home::Msg::EventReceived(event),
+                module_model,
+                &model.config,
+                &mut orders.proxy(Msg::HomeMsg),
+            ),
+            PageModel::Session(module_model) => page::session::update(
+                page::session::Msg::EventReceived(event),
+                module_model,
+                &model.config,
+                &mut orders.proxy(Msg::SessionMsg),
+            ),
+            PageModel::Settings(module_model) => page::settings::update(
+                page::settings::Msg::EventReceived(event),
+                module_model,
+                &model.config,
+                &mut orders.proxy(Msg::SettingsMsg),
+            ),
+            PageModel::History(module_model) => page::history::update(
+                page::history::Msg::EventReceived(event),
+                module_model,
+                &model.config,
+                &mut orders.proxy(Msg::HistoryMsg),
+            ),
+            PageModel::Employee(module_model) => page::employee::update(
+                page::employee::Msg::EventReceived(event),
+                module_model,
+                &model.config,
+                &mut orders.proxy(Msg::EmployeeMsg),
+            ),
+            _ => (),
+        },
+        Msg::LogOut => {
+            orders.send_g_msg(GMsg::LoggedOut);
+        }
+        Msg::Online => {
+            send_next_mutation(model, orders);
+        }
+        Msg::MutationSent(Ok(())) => {
+            model.outbox.done();
+            mutation_replayed(model, orders);
+        }
+        Msg::MutationSent(Err(errors)) => {
+            if errors.iter().all(|error| error.kind().is_transient()) {
+                // Still offline, or the server is down: the next `online` event sends it again.
+                model.outbox.pause();
+            } else if errors.iter().any(ErrorMessage::is_unauthorized) {
+                // Sent again once the user has logged in.
+                model.outbox.pause();
+                orders.send_g_msg(GMsg::LoggedOut);
+            } else if let Some(mutation) = model.outbox.done() {
+                // Made obsolete by changes from other people while offline.
+                notification::notify_failure(&mutation.description(), &errors, orders);
+                mutation_replayed(model, orders);
+            }
+        }
+    }
+}
+
+fn send_next_mutation(model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    if outbox::is_offline() || model.session.is_none() {
+        return;
+    }
+    let config = &model.config;
+    match model.outbox.next() {
+        Some(Mutation::AddEmployee { group, name }) => {
+            orders.perform_cmd(request::request::add_employee(
+                config,
+                &group,
+                name,
+                |result| Msg::MutationSent(result.map(|_| ())),
+            ));
+        }
+        Some(Mutation::RemoveEmployee {
+            group, employee_id, ..
+        }) => {
+            orders.perform_cmd(request::request::remove_employee(
+                config,
+                &group,
+                employee_id,
+                |result| Msg::MutationSent(result.map(|_| ())),
+            ));
+        }
+        Some(Mutation::AcceptPick {
+            group, employee_id, ..
+        }) => {
+            orders.perform_cmd(request::request::accept_pick(
+                config,
+                &group,
+                employee_id,
+                |result| Msg::MutationSent(result.map(|_| ())),
+            ));
+        }
+        None => (),
+    }
+}
+
+fn mutation_replayed(model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    if model.outbox.is_empty() {
+        orders.send_g_msg(GMsg::Notify(Notification::success(
+            "The changes made offline have been synchronised",
+        )));
+        // The shown data predates the changes, the page is started over to load it again.
+        model.page = PageModel::Redirect;
+        change_model_by_route(seed::browser::url::current().try_into().ok(), model, orders);
+    } else {
+        send_next_mutation(model, orders);
     }
 }
 
@@ -100,36 +418,192 @@ fn change_model_by_route(
     model: &mut Model,
     orders: &mut impl Orders<Msg, GMsg>,
 ) {
+    let user = model.session.as_ref().map(|session| &session.user);
+    if let Some(route) = route.as_ref().filter(|route| !route.is_allowed(user)) {
+        match (user, route.group()) {
+            (None, _) => route::go_to(Route::Login, orders),
+            (Some(_), group) => {
+                orders.send_g_msg(GMsg::Notify(Notification::warning(
+                    "You are not allowed to open this page",
+                )));
+                route::go_to(group.cloned().map_or(Route::Root, Route::Home), orders);
+            }
+        }
+        return;
+    }
+
+    model.group = route.as_ref().and_then(Route::group).cloned();
+    if model.group.is_some() && model.group != model.preferences.group {
+        model.preferences.group = model.group.clone();
+        storage::save_preferences(&model.preferences);
+    }
+    subscribe(model, orders);
     match route {
-        None => *model = Model::NotFound,
+        None => model.page = PageModel::NotFound,
         Some(route) => match route {
-            Route::Root => route::go_to(Route::Home, orders),
-            Route::Settings => {
-                *model = Model::Settings(page::settings::init(&mut orders.proxy(Msg::SettingsMsg)));
+            Route::Login => {
+                model.page = PageModel::Login(page::login::init());
+            }
+            Route::Root => {
+                model.page = PageModel::Groups(page::groups::init(
+                    &model.config,
+                    &mut orders.proxy(Msg::GroupsMsg),
+                ));
+            }
+            Route::Employee(group, employee_id) => {
+                model.page = PageModel::Employee(page::employee::init(
+                    &model.config,
+                    group,
+                    employee_id,
+                    &mut orders.proxy(Msg::EmployeeMsg),
+                ));
+            }
+            Route::Settings { group, tab } => {
+                if let PageModel::Settings(module_model) = &mut model.page {
+                    if page::settings::group(module_model) == &group {
+                        page::settings::change_tab(module_model, tab);
+                        return;
+                    }
+                }
+                model.page = PageModel::Settings(page::settings::init(
+                    &model.config,
+                    group,
+                    tab,
+                    &model.preferences,
+                    &mut orders.proxy(Msg::SettingsMsg),
+                ));
             }
-            Route::Home => {
-                *model = Model::Home(page::home::init(&mut orders.proxy(Msg::HomeMsg)));
+            Route::History {
+                group,
+                page,
+                person,
+            } => {
+                if let PageModel::History(module_model) = &mut model.page {
+                    if page::history::group(module_model) == &group {
+                        page::history::change_page(module_model, page, person);
+                        return;
+                    }
+                }
+                model.page = PageModel::History(page::history::init(
+                    &model.config,
+                    group,
+                    page,
+                    person,
+                    &mut orders.proxy(Msg::HistoryMsg),
+                ));
+            }
+            Route::Session(group) => {
+                model.page = PageModel::Session(page::session::init(
+                    &model.config,
+                    group,
+                    &mut orders.proxy(Msg::SessionMsg),
+                ));
+            }
+            Route::Home(group) => {
+                model.page = PageModel::Home(page::home::init(
+                    &model.config,
+                    group,
+                    &model.preferences,
+                    &mut orders.proxy(Msg::HomeMsg),
+                ));
             }
         },
     };
 }
 
+/// Follows the changes made by other people to the group of the current page.
+fn subscribe(model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    let group = match (&model.session, &model.group) {
+        (Some(_), Some(group)) => group,
+        _ => {
+            model.subscription = None;
+            return;
+        }
+    };
+    if model.subscription.as_ref().map(events::Subscription::group) == Some(group) {
+        return;
+    }
+    let (app, msg_mapper) = (orders.clone_app(), orders.msg_mapper());
+    model.subscription = events::Subscription::new(&model.config, group.clone(), move |event| {
+        app.update(msg_mapper(Msg::EventReceived(event)))
+    });
+}
+
+/// The groups listed in the navbar.
+fn load_groups(config: &Config) -> impl Future<Output = Result<Msg, Msg>> {
+    let groups = request::request::load_groups(config);
+    async move { Ok(Msg::GroupsLoaded(groups.await)) }
+}
+
 // ------ ------
 //     View
 // ------ ------
 
 fn view(model: &Model) -> impl View<Msg> {
-    use page::Page;
-    match model {
-        Model::Redirect => Page::Other.view(page::blank::view()),
-        Model::NotFound => Page::Other.view(page::not_found::view()),
-        Model::Settings(model) => Page::Settings
-            .view(page::settings::view::<page::settings::Model>(model))
-            .map_msg(Msg::SettingsMsg),
-        Model::Home(model) => Page::Home
-            .view(page::home::view::<page::home::Model>(model))
-            .map_msg(Msg::HomeMsg),
+    use page::{Navbar, Page, ViewPage};
+    let navbar = Navbar {
+        groups: &model.groups,
+        group: model.group.as_ref(),
+        theme: model.preferences.theme,
+        theme_changed: Msg::ThemeChanged,
+        user: model.session.as_ref().map(|session| &session.user),
+        logged_out: || Msg::LogOut,
+    };
+    let user = navbar.user;
+    let view_page = |page: Page, view_page: ViewPage<Msg>| page.view(&navbar, view_page);
+    let mut nodes = match &model.page {
+        PageModel::Redirect => view_page(Page::Other, page::blank::view()),
+        PageModel::NotFound => view_page(Page::Other, page::not_found::view()),
+        PageModel::Login(model) => {
+            view_page(Page::Other, page::login::view(model).map_msg(Msg::LoginMsg))
+        }
+        PageModel::Groups(model) => view_page(
+            Page::Groups,
+            page::groups::view(model, user).map_msg(Msg::GroupsMsg),
+        ),
+        PageModel::Settings(model) => view_page(
+            Page::Settings,
+            page::settings::view(model).map_msg(Msg::SettingsMsg),
+        ),
+        PageModel::History(model) => view_page(
+            Page::History,
+            page::history::view(model, user).map_msg(Msg::HistoryMsg),
+        ),
+        PageModel::Employee(model) => view_page(
+            Page::Other,
+            page::employee::view(model).map_msg(Msg::EmployeeMsg),
+        ),
+        PageModel::Home(model) => view_page(
+            Page::Home,
+            page::home::view(model, user).map_msg(Msg::HomeMsg),
+        ),
+        PageModel::Session(model) => view_page(
+            Page::Session,
+            page::session::view(model).map_msg(Msg::SessionMsg),
+        ),
+    };
+    nodes.push(page::view_notifications(
+        Msg::NotificationDismissed,
+        &model.notifications,
+    ));
+    nodes.push(page::view_outbox(model.outbox.len()));
+    nodes
+}
+
+// ------ ------
+// Window Events
+// ------ ------
+
+fn window_events(model: &Model) -> Vec<EventHandler<Msg>> {
+    let mut events = vec![ev(Ev::from("online"), |_| Msg::Online)];
+    if let PageModel::Home(module_model) = &model.page {
+        events.extend(
+            page::home::window_events(module_model)
+                .into_iter()
+                .map(|event| event.map_msg(Msg::HomeMsg)),
+        );
     }
+    events
 }
 
 // ------ ------
@@ -143,5 +617,6 @@ pub fn start() {
         .after_mount(after_mount)
         .routes(|url| Some(Msg::RouteChanged(url.try_into().ok())))
         .sink(sink)
+        .window_events(window_events)
         .build_and_start();
 }
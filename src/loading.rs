@@ -1,24 +1,175 @@
+use crate::image::Image;
+use crate::notification;
+use crate::GMsg;
+use caliaconf_api::entity::ErrorMessage;
 use gloo_timers::future::TimeoutFuture;
 use seed::{prelude::*, *};
+use std::future::Future;
+use std::mem;
 
 const SLOW_LOADING_THRESHOLD_MS: u32 = 500;
+const RETRY_BASE_DELAY_MS: u32 = 1_000;
+/// Automatic retries of a failed load before giving up.
+pub const MAX_RETRIES: u32 = 3;
 
-pub async fn notify_on_slow_load<Ms>(msg: Ms) -> Result<Ms, Ms> {
+/// Result of a load, sent to the page once the automatic retries are over.
+pub type Loaded<T> = Result<T, Vec<ErrorMessage>>;
+
+// ------ Status ------
+
+/// Data loaded from the server.
+#[derive(Default)]
+pub enum Status<T> {
+    #[default]
+    Loading,
+    /// Loading for a while, the loading icon is shown.
+    LoadingSlowly,
+    Loaded(T),
+    /// Shown while it's loaded again, it may be outdated.
+    Refreshing(T),
+    Failed(Vec<ErrorMessage>),
+}
+
+impl<T> Status<T> {
+    /// Shows the `cached` data until it's loaded, if there is some.
+    pub fn new(cached: Option<T>) -> Self {
+        cached.map_or(Status::Loading, Status::Refreshing)
+    }
+
+    pub fn loaded(&self) -> Option<&T> {
+        match self {
+            Status::Loaded(data) | Status::Refreshing(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn loaded_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Status::Loaded(data) | Status::Refreshing(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Loads again, the data already loaded is shown meanwhile.
+    pub fn reload(&mut self) {
+        *self = match mem::take(self) {
+            Status::Loaded(data) | Status::Refreshing(data) => Status::Refreshing(data),
+            _ => Status::Loading,
+        }
+    }
+
+    /// The data already loaded is kept, however old, when loading it again fails.
+    pub fn finish(&mut self, result: Result<T, Vec<ErrorMessage>>) {
+        *self = match (result, mem::take(self)) {
+            (Ok(data), _) => Status::Loaded(data),
+            (Err(_), Status::Loaded(data)) | (Err(_), Status::Refreshing(data)) => {
+                Status::Loaded(data)
+            }
+            (Err(errors), _) => Status::Failed(errors),
+        }
+    }
+
+    pub fn slow_load_threshold_passed(&mut self) {
+        if let Status::Loading = self {
+            *self = Status::LoadingSlowly
+        }
+    }
+}
+
+/// Loads the `status`, its data already loaded is shown meanwhile.
+///
+/// The `request` is sent again while it fails with transient errors, up to `MAX_RETRIES` times,
+/// before its result is sent with `loaded`.
+/// `slow_load_threshold_passed` is sent if it takes a while.
+pub fn start<T, R, Ms, GMs>(
+    status: &mut Status<T>,
+    request: impl Fn() -> R + 'static,
+    loaded: fn(Loaded<T>) -> Ms,
+    slow_load_threshold_passed: Ms,
+    orders: &mut impl Orders<Ms, GMs>,
+) where
+    T: 'static,
+    R: Future<Output = Loaded<T>>,
+    Ms: 'static,
+{
+    status.reload();
+    orders
+        .perform_cmd(notify_on_slow_load(slow_load_threshold_passed))
+        .perform_cmd(async move {
+            let mut retries = 0;
+            loop {
+                match request().await {
+                    Err(errors) if should_retry(retries, &errors) => {
+                        retries += 1;
+                        retry_after(retries).await;
+                    }
+                    result => return Ok(loaded(result)),
+                }
+            }
+        });
+}
+
+/// Shows the `result` of a load, its errors are notified as `action` failures.
+pub fn finish<T, Ms: 'static>(
+    status: &mut Status<T>,
+    result: Loaded<T>,
+    action: &str,
+    orders: &mut impl Orders<Ms, GMsg>,
+) {
+    if let Err(errors) = &result {
+        notification::notify_failure(action, errors, orders);
+    }
+    status.finish(result);
+}
+
+async fn notify_on_slow_load<Ms>(msg: Ms) -> Result<Ms, Ms> {
     TimeoutFuture::new(SLOW_LOADING_THRESHOLD_MS).await;
     Ok(msg)
 }
 
+/// Whether a load failing with the `errors` after `retries` retries should be retried.
+fn should_retry(retries: u32, errors: &[ErrorMessage]) -> bool {
+    retries < MAX_RETRIES && errors.iter().all(|error| error.kind().is_transient())
+}
+
+/// Waits before the given retry with an exponential backoff: 1s, 2s, 4s...
+async fn retry_after(retry: u32) {
+    TimeoutFuture::new(RETRY_BASE_DELAY_MS << retry.saturating_sub(1).min(16)).await;
+}
+
 // ------ view functions ------
 
+/// The `subject` with `view_loaded`, or its loading state, or its error with a button sending `retry`.
+pub fn view<T, Ms: Clone>(
+    status: &Status<T>,
+    subject: &str,
+    retry: Ms,
+    view_loaded: impl FnOnce(&T) -> Node<Ms>,
+) -> Node<Ms> {
+    match status {
+        Status::Loading => empty![],
+        Status::LoadingSlowly => view_icon(),
+        Status::Loaded(data) | Status::Refreshing(data) => view_loaded(data),
+        Status::Failed(errors) => view_error(subject, errors, retry),
+    }
+}
+
 pub fn view_icon<Ms>() -> Node<Ms> {
     img![attrs! {
-        At::Src => "Image::loading().url()",
+        At::Src => Image::loading().url(),
         At::Width => 64,
         At::Height => 64,
         At::Alt => "Loading..."
     }]
 }
 
-pub fn view_error<Ms>(subject: &str) -> Node<Ms> {
-    div![format!("Error loading {}.", subject)]
+pub fn view_error<Ms: Clone>(subject: &str, errors: &[ErrorMessage], retry: Ms) -> Node<Ms> {
+    div![
+        class!["container"],
+        p![format!("Error loading {}.", subject)],
+        errors
+            .iter()
+            .map(|error| p![class!["help is-danger"], error.to_string()]),
+        button![class!["button"], simple_ev(Ev::Click, retry), "Retry"],
+    ]
 }
@@ -0,0 +1,127 @@
+use crate::GMsg;
+use caliaconf_api::entity::{ErrorKind, ErrorMessage};
+use gloo_timers::future::TimeoutFuture;
+use seed::prelude::*;
+use std::borrow::Cow;
+use std::future::Future;
+
+pub type NotificationId = u32;
+
+// ------ Severity ------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// How long a notification stays on screen if it isn't dismissed.
+    const fn expiration_ms(self) -> u32 {
+        match self {
+            Severity::Info | Severity::Success => 4_000,
+            Severity::Warning => 8_000,
+            Severity::Error => 12_000,
+        }
+    }
+}
+
+// ------ Notification ------
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: Cow<'static, str>,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+        }
+    }
+
+    pub fn info(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(Severity::Info, message)
+    }
+
+    pub fn success(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(Severity::Success, message)
+    }
+
+    pub fn warning(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn error(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// E.g. "Remove failed: timeout" or "Add failed: Name is required".
+    pub fn failure(action: &str, error: &ErrorMessage) -> Self {
+        let reason = match error.kind() {
+            ErrorKind::Validation | ErrorKind::Server => error.to_string(),
+            kind => kind.to_string(),
+        };
+        Self::error(format!("{} failed: {}", action, reason))
+    }
+}
+
+// ------ Notifications ------
+
+/// Notifications currently displayed, oldest first.
+#[derive(Default)]
+pub struct Notifications {
+    next_id: NotificationId,
+    items: Vec<(NotificationId, Notification)>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, notification: Notification) -> NotificationId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.items.push((id, notification));
+        id
+    }
+
+    pub fn dismiss(&mut self, id: NotificationId) {
+        self.items.retain(|(item_id, _)| *item_id != id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(NotificationId, Notification)> {
+        self.items.iter()
+    }
+}
+
+/// Also sends the user back to the login page when the session has expired.
+pub fn notify_failure<Ms: 'static>(
+    action: &str,
+    errors: &[ErrorMessage],
+    orders: &mut impl Orders<Ms, GMsg>,
+) {
+    if errors.iter().any(ErrorMessage::is_unauthorized) {
+        orders.send_g_msg(GMsg::LoggedOut);
+    }
+    for error in errors {
+        orders.send_g_msg(GMsg::Notify(Notification::failure(action, error)));
+    }
+}
+
+pub fn expire<Ms: 'static>(
+    id: NotificationId,
+    severity: Severity,
+    f: fn(NotificationId) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    let timeout = TimeoutFuture::new(severity.expiration_ms());
+    async move {
+        timeout.await;
+        Ok(f(id))
+    }
+}
@@ -0,0 +1,100 @@
+//! Changes made while offline, kept in the local storage and sent in order once the connection is back.
+
+use crate::storage;
+use caliaconf_api::entity::{EmployeeId, GroupSlug};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Whether the browser knows it has no connection.
+pub fn is_offline() -> bool {
+    !seed::window().navigator().on_line()
+}
+
+// ------ Mutation ------
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "action")]
+pub enum Mutation {
+    AddEmployee {
+        group: GroupSlug,
+        name: String,
+    },
+    RemoveEmployee {
+        group: GroupSlug,
+        employee_id: EmployeeId,
+        name: String,
+    },
+    AcceptPick {
+        group: GroupSlug,
+        employee_id: EmployeeId,
+        name: String,
+    },
+}
+
+impl Mutation {
+    pub fn description(&self) -> String {
+        match self {
+            Mutation::AddEmployee { name, .. } => format!("Add {}", name),
+            Mutation::RemoveEmployee { name, .. } => format!("Remove {}", name),
+            Mutation::AcceptPick { name, .. } => format!("Pick {}", name),
+        }
+    }
+}
+
+// ------ Outbox ------
+
+#[derive(Default)]
+pub struct Outbox {
+    mutations: VecDeque<Mutation>,
+    /// The first mutation has been sent, its response is awaited.
+    sending: bool,
+}
+
+impl Outbox {
+    pub fn load() -> Self {
+        Self {
+            mutations: storage::load_outbox().into(),
+            sending: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.mutations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mutations.is_empty()
+    }
+
+    pub fn push(&mut self, mutation: Mutation) {
+        self.mutations.push_back(mutation);
+        self.save();
+    }
+
+    /// The mutation to send, unless one is already being sent.
+    pub fn next(&mut self) -> Option<Mutation> {
+        if self.sending {
+            return None;
+        }
+        let mutation = self.mutations.front().cloned();
+        self.sending = mutation.is_some();
+        mutation
+    }
+
+    /// The mutation being sent has been applied, or rejected for good.
+    pub fn done(&mut self) -> Option<Mutation> {
+        self.sending = false;
+        let mutation = self.mutations.pop_front();
+        self.save();
+        mutation
+    }
+
+    /// The mutation being sent couldn't reach the server, it will be sent again.
+    pub fn pause(&mut self) {
+        self.sending = false;
+    }
+
+    fn save(&self) {
+        storage::save_outbox(&self.mutations.iter().cloned().collect::<Vec<_>>());
+    }
+}
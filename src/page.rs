@@ -1,41 +1,82 @@
-use crate::{entity::ErrorMessage, Route};
+use crate::notification::{NotificationId, Notifications, Severity};
+use crate::theme::Theme;
+use crate::Route;
+use caliaconf_api::entity::{Group, GroupSlug, Talk, User};
 use seed::{prelude::*, *};
 use std::borrow::Cow;
 
 pub mod blank;
+pub mod employee;
+pub mod groups;
+pub mod history;
 pub mod home;
+pub mod login;
 pub mod not_found;
+pub mod session;
 pub mod settings;
 
+#[allow(dead_code)]
 pub fn scroll_to_top() {
-    seed::window().scroll_to_with_scroll_to_options(
-        web_sys::ScrollToOptions::new()
-            .top(0.)
-            .left(0.)
-            .behavior(web_sys::ScrollBehavior::Smooth),
-    )
+    let options = web_sys::ScrollToOptions::new();
+    options.set_top(0.);
+    options.set_left(0.);
+    options.set_behavior(web_sys::ScrollBehavior::Smooth);
+    seed::window().scroll_to_with_scroll_to_options(&options)
 }
 
-pub fn view_errors<Ms: Clone>(dismiss_errors: Ms, errors: &[ErrorMessage]) -> Node<Ms> {
-    if errors.is_empty() {
+pub fn view_notifications<Ms: 'static>(
+    dismiss: fn(NotificationId) -> Ms,
+    notifications: &Notifications,
+) -> Node<Ms> {
+    if notifications.is_empty() {
         empty![]
     } else {
         div![
-            class!["error-messages"],
-            style! {
-                "position" => "fixed",
-                "top" => 0,
-                "background" => "rgb(250, 250, 250)",
-                "padding" => "20px",
-                "border" => "1px solid",
-                "z-index" => 9999,
-            },
-            errors.iter().map(|error| p![error]),
-            button![simple_ev(Ev::Click, dismiss_errors), "Ok"]
+            class!["notifications"],
+            notifications.iter().map(|(id, notification)| {
+                let id = *id;
+                div![
+                    class!["notification", severity_class(notification.severity)],
+                    button![class!["delete"], ev(Ev::Click, move |_| dismiss(id))],
+                    notification.message,
+                ]
+            })
+        ]
+    }
+}
+
+/// The number of changes made offline and not sent yet.
+pub fn view_outbox<Ms>(pending: usize) -> Node<Ms> {
+    if pending == 0 {
+        empty![]
+    } else {
+        div![
+            class!["outbox notification is-warning"],
+            format!("{} change(s) waiting for the connection", pending),
         ]
     }
 }
 
+/// Link to the slides of the `talk`, left out unless they are an http or https URL.
+pub fn view_slides<Ms>(talk: &Talk) -> Node<Ms> {
+    match talk.slides_link() {
+        Some(slides) => a![
+            attrs! {At::Href => slides, At::Target => "_blank", At::Rel => "noopener noreferrer"},
+            "Slides"
+        ],
+        None => empty![],
+    }
+}
+
+fn severity_class(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "is-info",
+        Severity::Success => "is-success",
+        Severity::Warning => "is-warning",
+        Severity::Error => "is-danger",
+    }
+}
+
 // ------ ViewPage ------
 
 #[allow(clippy::module_name_repetitions)]
@@ -54,35 +95,67 @@ impl<'a, Ms> ViewPage<'a, Ms> {
     pub fn title(&self) -> String {
         format!("{} - Caliaconf", self.title_prefix)
     }
+    pub fn map_msg<OtherMs: 'static>(
+        self,
+        f: impl FnOnce(Ms) -> OtherMs + 'static + Clone,
+    ) -> ViewPage<'a, OtherMs> {
+        ViewPage {
+            title_prefix: self.title_prefix,
+            content: self.content.map_msg(f),
+        }
+    }
     #[allow(clippy::missing_const_for_fn)]
     pub fn into_content(self) -> Node<Ms> {
         self.content
     }
 }
 
+// ------ Navbar ------
+
+/// What the navbar shows besides the links to the pages.
+pub struct Navbar<'a, Ms> {
+    pub groups: &'a [Group],
+    /// The group of the current page.
+    pub group: Option<&'a GroupSlug>,
+    pub theme: Theme,
+    pub theme_changed: fn(Theme) -> Ms,
+    /// `None` when nobody is logged in.
+    pub user: Option<&'a User>,
+    pub logged_out: fn() -> Ms,
+}
+
 // ------ Page ------
 
 pub enum Page {
     Other,
+    Groups,
     Home,
+    Session,
     Settings,
+    History,
 }
 
 #[allow(clippy::unused_self)]
 impl Page {
     fn is_active(&self, route: &Route) -> bool {
-        match (self, route) {
-            (Page::Home, Route::Home) | (Page::Settings, Route::Settings) => true,
-            _ => false,
-        }
+        matches!(
+            (self, route),
+            (Page::Groups, Route::Root)
+                | (Page::Home, Route::Home(_))
+                | (Page::Session, Route::Session(_))
+                | (Page::Settings, Route::Settings { .. })
+                | (Page::History, Route::History { .. })
+        )
     }
 
     // ------ view methods ------
 
-    pub fn view<Ms>(&self, view_page: ViewPage<Ms>) -> Vec<Node<Ms>> {
+    /// The navbar links to the pages of the group the user may open, and to the other groups.
+    /// It also switches the theme and logs the user out.
+    pub fn view<Ms: 'static>(&self, navbar: &Navbar<Ms>, view_page: ViewPage<Ms>) -> Vec<Node<Ms>> {
         seed::document().set_title(&view_page.title());
 
-        self.view_header()
+        self.view_header(navbar)
             .into_iter()
             .chain(vec![view_page.into_content(), self.view_footer()])
             .collect()
@@ -90,7 +163,19 @@ impl Page {
 
     // ====== PRIVATE ======
 
-    fn view_header<Ms>(&self) -> Vec<Node<Ms>> {
+    fn view_header<Ms: 'static>(&self, navbar: &Navbar<Ms>) -> Vec<Node<Ms>> {
+        let home = navbar
+            .group
+            .map_or(Route::Root, |group| Route::Home(group.clone()));
+        let links = navbar.group.map_or_else(Vec::new, |group| {
+            vec![
+                (Route::Home(group.clone()), "Home"),
+                (Route::Session(group.clone()), "Session"),
+                (Route::history(group.clone()), "History"),
+                (Route::settings(group.clone()), "Settings"),
+            ]
+        });
+
         vec![
             nav![
                 class!["navbar"],
@@ -98,7 +183,7 @@ impl Page {
                     class!["navbar-brand"],
                     a![
                         class!["navbar-item"],
-                        attrs! {At::Href => Route::Home.to_string()},
+                        attrs! {At::Href => home.to_string()},
                         img![
                             attrs! {At::Src => "https://www.caliatys.com/wp-content/uploads/2018/04/rectangleLogo-1.png"}
                         ],
@@ -115,8 +200,22 @@ impl Page {
                     class!["navbar-menu"],
                     div![
                         class!["navbar-start"],
-                        self.view_navbar_link(&Route::Home, "Home"),
-                        self.view_navbar_link(&Route::Settings, "Settings"),
+                        links
+                            .iter()
+                            .filter(|(route, _)| route.is_allowed(navbar.user))
+                            .map(|(route, label)| self.view_navbar_link(route, *label)),
+                    ],
+                    div![
+                        class!["navbar-end"],
+                        match navbar.user {
+                            Some(_) => self.view_group_switcher(navbar.groups, navbar.group),
+                            None => empty![],
+                        },
+                        self.view_theme_switcher(navbar.theme, navbar.theme_changed),
+                        match navbar.user {
+                            Some(user) => self.view_user(user, navbar.logged_out),
+                            None => empty![],
+                        },
                     ],
                 ],
             ],
@@ -136,21 +235,15 @@ impl Page {
                 p![
                     strong!["CaliaConf "],
                     "let's bring Rust to the front-end with ",
-                    a![
-                        attrs! {At::Href => "https://seed-rs.org/"},
-                        "Seed"
-                    ],
+                    a![attrs! {At::Href => "https://seed-rs.org/"}, "Seed"],
                     " (and ",
-                    a![
-                        attrs! { At::Href => "https://bulma.io/" },
-                        "Bulma",
-                    ],
+                    a![attrs! { At::Href => "https://bulma.io/" }, "Bulma",],
                     ") and ",
                     a![
-                        attrs! {At::Href => "https://darklang.com/"},
-                        "Dark"
+                        attrs! {At::Href => "https://github.com/tiny-http/tiny-http"},
+                        "tiny-http"
                     ],
-                     " at the back-end to pick the next speaker"
+                    " at the back-end to pick the next speaker"
                 ]
             ]
         ]
@@ -158,6 +251,81 @@ impl Page {
 
     // ------ view_header helpers ------
 
+    fn view_group_switcher<Ms>(&self, groups: &[Group], group: Option<&GroupSlug>) -> Node<Ms> {
+        let current_group = groups
+            .iter()
+            .find(|other| Some(&other.slug) == group)
+            .map_or("Groups", |group| group.name.as_str());
+
+        div![
+            class!["navbar-item has-dropdown is-hoverable"],
+            a![class!["navbar-link"], current_group],
+            div![
+                class!["navbar-dropdown is-right"],
+                groups.iter().map(|other| a![
+                    class![
+                        "navbar-item",
+                        "is-active" => Some(&other.slug) == group,
+                    ],
+                    attrs! {At::Href => Route::Home(other.slug.clone()).to_string()},
+                    other.name,
+                ]),
+                hr![class!["navbar-divider"]],
+                self.view_navbar_link(&Route::Root, "All the groups"),
+            ],
+        ]
+    }
+
+    fn view_theme_switcher<Ms: 'static>(
+        &self,
+        theme: Theme,
+        theme_changed: fn(Theme) -> Ms,
+    ) -> Node<Ms> {
+        let view_icon = |theme: Theme| span![class!["icon"], i![class!["fas", theme.icon()]]];
+
+        div![
+            class!["navbar-item has-dropdown is-hoverable"],
+            a![
+                class!["navbar-link"],
+                attrs! {At::Title => "Theme"},
+                view_icon(theme)
+            ],
+            div![
+                class!["navbar-dropdown is-right"],
+                Theme::ALL.iter().map(|other| {
+                    let other = *other;
+                    a![
+                        class!["navbar-item", "is-active" => other == theme],
+                        ev(Ev::Click, move |_| theme_changed(other)),
+                        view_icon(other),
+                        span![other.label()],
+                    ]
+                }),
+            ],
+        ]
+    }
+
+    fn view_user<Ms: 'static>(&self, user: &User, logged_out: fn() -> Ms) -> Node<Ms> {
+        div![
+            class!["navbar-item has-dropdown is-hoverable"],
+            a![
+                class!["navbar-link"],
+                span![class!["icon"], i![class!["fas fa-user"]]],
+                span![user.name],
+            ],
+            div![
+                class!["navbar-dropdown is-right"],
+                div![class!["navbar-item"], user.role.label()],
+                hr![class!["navbar-divider"]],
+                a![
+                    class!["navbar-item"],
+                    ev(Ev::Click, move |_| logged_out()),
+                    "Log out",
+                ],
+            ],
+        ]
+    }
+
     fn view_navbar_link<Ms>(&self, route: &Route, link_content: impl UpdateEl<El<Ms>>) -> Node<Ms> {
         a![
             class![
@@ -0,0 +1,266 @@
+use super::{history, view_slides, ViewPage};
+use crate::config::Config;
+use crate::loading::{self, Loaded, Status};
+use crate::notification;
+use crate::request::request;
+use crate::{GMsg, Route};
+use caliaconf_api::endpoint::TalkHistory;
+use caliaconf_api::entity::{
+    absence, Absence, Employee, EmployeeId, ErrorMessage, GroupSlug, Talk, TalkStatus,
+};
+use caliaconf_api::event::Event;
+use seed::{prelude::*, *};
+use std::cmp::Reverse;
+
+#[derive(Default)]
+pub struct Model {
+    group: GroupSlug,
+    employee_id: EmployeeId,
+    history: Status<TalkHistory>,
+    absences: Vec<Absence>,
+}
+
+/// Figures about the talks given by one person.
+struct Stats {
+    talks_given: usize,
+    /// Average number of days between two confirmed talks.
+    average_gap: Option<i64>,
+}
+
+impl Stats {
+    fn new(talks: &[&Talk]) -> Self {
+        let mut dates = talks
+            .iter()
+            .filter(|talk| talk.status == TalkStatus::Confirmed)
+            .map(|talk| talk.date)
+            .collect::<Vec<_>>();
+        dates.sort();
+
+        let gaps = dates
+            .windows(2)
+            .map(|dates| (dates[1] - dates[0]).num_days())
+            .collect::<Vec<_>>();
+        Stats {
+            talks_given: dates.len(),
+            average_gap: if gaps.is_empty() {
+                None
+            } else {
+                Some(gaps.iter().sum::<i64>() / gaps.len() as i64)
+            },
+        }
+    }
+}
+
+pub fn init(
+    config: &Config,
+    group: GroupSlug,
+    employee_id: EmployeeId,
+    orders: &mut impl Orders<Msg, GMsg>,
+) -> Model {
+    orders.perform_cmd(request::load_absences(config, &group, Msg::AbsencesLoaded));
+
+    let mut model = Model {
+        group,
+        employee_id,
+        ..Model::default()
+    };
+    load_history(&mut model, config, orders);
+    model
+}
+
+#[derive(Clone)]
+pub enum Msg {
+    HistoryLoaded(Loaded<TalkHistory>),
+    ReloadHistory,
+    AbsencesLoaded(Result<Vec<Absence>, Vec<ErrorMessage>>),
+    EventReceived(Event),
+    SlowLoadThresholdPassed,
+}
+
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    match msg {
+        Msg::HistoryLoaded(result) => {
+            loading::finish(&mut model.history, result, "Loading talks", orders);
+        }
+        Msg::ReloadHistory => {
+            load_history(model, config, orders);
+        }
+        Msg::AbsencesLoaded(Ok(absences)) => {
+            model.absences = absences;
+        }
+        Msg::AbsencesLoaded(Err(errors)) => {
+            notification::notify_failure("Loading absences", &errors, orders);
+        }
+        Msg::EventReceived(Event::AbsencesChanged(absences)) => {
+            model.absences = absences;
+        }
+        Msg::EventReceived(event) => {
+            history::apply_event(&mut model.history, event, Msg::ReloadHistory, orders);
+        }
+        Msg::SlowLoadThresholdPassed => {
+            model.history.slow_load_threshold_passed();
+        }
+    }
+}
+
+fn load_history(model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    let (config, group) = (config.clone(), model.group.clone());
+    loading::start(
+        &mut model.history,
+        move || request::load_talks(&config, &group),
+        Msg::HistoryLoaded,
+        Msg::SlowLoadThresholdPassed,
+        orders,
+    );
+}
+
+pub fn view<'a>(model: &Model) -> ViewPage<'a, Msg> {
+    let title = model
+        .history
+        .loaded()
+        .and_then(|(employees, _)| {
+            employees
+                .iter()
+                .find(|employee| employee.uuid == model.employee_id)
+        })
+        .map_or_else(|| "Employee".to_owned(), |employee| employee.name.clone());
+    ViewPage::new(title, view_content(model))
+}
+
+fn view_content(model: &Model) -> Node<Msg> {
+    loading::view(
+        &model.history,
+        "talks",
+        Msg::ReloadHistory,
+        |(employees, talks)| {
+            let employee = match employees
+                .iter()
+                .find(|employee| employee.uuid == model.employee_id)
+            {
+                Some(employee) => employee,
+                None => {
+                    return div![
+                        class!["container"],
+                        p!["This person is not part of the group anymore."],
+                    ]
+                }
+            };
+            let mut talks = talks
+                .iter()
+                .filter(|talk| talk.speaker_id == employee.uuid)
+                .collect::<Vec<_>>();
+            talks.sort_by_key(|talk| Reverse(talk.date));
+            let mut absences = model
+                .absences
+                .iter()
+                .filter(|absence| absence.employee_id == employee.uuid)
+                .collect::<Vec<_>>();
+            absences.sort_by_key(|absence| Reverse(absence.from));
+
+            div![
+                class!["container"],
+                h1![class!["title"], employee.name],
+                view_round_status(employee, &absences),
+                view_stats(&Stats::new(&talks)),
+                view_talks(&talks),
+                view_absences(&absences),
+                a![
+                    attrs! {At::Href => Route::History {
+                        group: model.group.clone(),
+                        page: 1,
+                        person: Some(employee.uuid),
+                    }
+                    .to_string()},
+                    "See their talks in the history",
+                ],
+            ]
+        },
+    )
+}
+
+fn view_round_status(employee: &Employee, absences: &[&Absence]) -> Node<Msg> {
+    let today = absence::today();
+    let return_date = absences
+        .iter()
+        .filter(|absence| absence.covers(today))
+        .map(|absence| absence.return_date())
+        .max();
+
+    div![
+        class!["tags"],
+        if employee.inactive {
+            span![class!["tag"], "Inactive"]
+        } else if employee.picked {
+            span![class!["tag is-success"], "Spoke this round"]
+        } else {
+            span![class!["tag is-info"], "Not picked yet this round"]
+        },
+        match return_date {
+            Some(return_date) => span![
+                class!["tag is-warning"],
+                format!("Absent, back on {}", return_date)
+            ],
+            None => empty![],
+        },
+    ]
+}
+
+fn view_stats(stats: &Stats) -> Node<Msg> {
+    nav![
+        class!["level"],
+        div![
+            class!["level-item has-text-centered"],
+            div![
+                p![class!["heading"], "Talks given"],
+                p![class!["title"], stats.talks_given.to_string()],
+            ],
+        ],
+        div![
+            class!["level-item has-text-centered"],
+            div![
+                p![class!["heading"], "Average gap between talks"],
+                p![
+                    class!["title"],
+                    stats
+                        .average_gap
+                        .map_or_else(|| "-".to_owned(), |days| format!("{} days", days)),
+                ],
+            ],
+        ],
+    ]
+}
+
+fn view_talks(talks: &[&Talk]) -> Node<Msg> {
+    if talks.is_empty() {
+        return p!["No talk yet."];
+    }
+    table![
+        class!["table is-fullwidth"],
+        thead![tr![th!["Date"], th!["Title"], th!["Slides"], th!["Status"]]],
+        tbody![talks.iter().map(|talk| tr![
+            td![talk.date.to_string()],
+            td![talk.title],
+            td![view_slides(talk)],
+            td![match talk.status {
+                TalkStatus::Pending => span![class!["tag is-warning"], "Pending"],
+                TalkStatus::Confirmed => span![class!["tag is-success"], "Confirmed"],
+                TalkStatus::Cancelled => span![class!["tag"], "Cancelled"],
+            }],
+        ])],
+    ]
+}
+
+fn view_absences(absences: &[&Absence]) -> Node<Msg> {
+    if absences.is_empty() {
+        return empty![];
+    }
+    table![
+        class!["table is-fullwidth"],
+        thead![tr![th!["Absent from"], th!["To"], th!["Reason"]]],
+        tbody![absences.iter().map(|absence| tr![
+            td![absence.from.to_string()],
+            td![absence.to.to_string()],
+            td![absence.reason],
+        ])],
+    ]
+}
@@ -0,0 +1,135 @@
+use super::ViewPage;
+use crate::config::Config;
+use crate::loading::{self, Loaded, Status};
+use crate::notification::{self, Notification};
+use crate::request::request;
+use crate::{route, GMsg, Route};
+use caliaconf_api::endpoint::{self, CreateGroup};
+use caliaconf_api::entity::{ErrorMessage, Group, User};
+use seed::{prelude::*, *};
+
+const ENTER_KEY: u32 = 13;
+
+#[derive(Default)]
+pub struct Model {
+    groups: Status<Vec<Group>>,
+    new_group_name: String,
+}
+
+pub fn init(config: &Config, orders: &mut impl Orders<Msg, GMsg>) -> Model {
+    let mut model = Model::default();
+    load_groups(&mut model, config, orders);
+    model
+}
+
+#[derive(Clone)]
+pub enum Msg {
+    GroupsLoaded(Loaded<Vec<Group>>),
+    ReloadGroups,
+    NewGroupNameChanged(String),
+    CreateGroup,
+    GroupCreated(Result<Vec<Group>, Vec<ErrorMessage>>),
+    SlowLoadThresholdPassed,
+    NoOp,
+}
+
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    match msg {
+        Msg::GroupsLoaded(result) => {
+            if let Ok(groups) = &result {
+                orders.send_g_msg(GMsg::GroupsChanged(groups.clone()));
+            }
+            loading::finish(&mut model.groups, result, "Loading groups", orders);
+        }
+        Msg::ReloadGroups => {
+            load_groups(model, config, orders);
+        }
+        Msg::NewGroupNameChanged(name) => {
+            model.new_group_name = name;
+        }
+        Msg::CreateGroup => {
+            orders.skip().perform_cmd(request::create_group(
+                config,
+                model.new_group_name.clone(),
+                Msg::GroupCreated,
+            ));
+        }
+        Msg::GroupCreated(Ok(groups)) => {
+            let created = model.groups.loaded().and_then(|previous_groups| {
+                groups
+                    .iter()
+                    .find(|group| !previous_groups.contains(group))
+                    .cloned()
+            });
+            orders.send_g_msg(GMsg::GroupsChanged(groups.clone()));
+            model.groups = Status::Loaded(groups);
+            model.new_group_name.clear();
+
+            if let Some(group) = created {
+                orders.send_g_msg(GMsg::Notify(Notification::success(format!(
+                    "{} created",
+                    group.name
+                ))));
+                route::go_to(Route::Home(group.slug), orders);
+            }
+        }
+        Msg::GroupCreated(Err(errors)) => {
+            notification::notify_failure("Create group", &errors, orders);
+        }
+        Msg::SlowLoadThresholdPassed => {
+            model.groups.slow_load_threshold_passed();
+        }
+        Msg::NoOp => (),
+    }
+}
+
+fn load_groups(model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    let config = config.clone();
+    loading::start(
+        &mut model.groups,
+        move || request::load_groups(&config),
+        Msg::GroupsLoaded,
+        Msg::SlowLoadThresholdPassed,
+        orders,
+    );
+}
+
+/// Only the admins can create a group.
+pub fn view<'a>(model: &Model, user: Option<&User>) -> ViewPage<'a, Msg> {
+    ViewPage::new("Groups", view_content(model, user))
+}
+
+fn view_content(model: &Model, user: Option<&User>) -> Node<Msg> {
+    loading::view(&model.groups, "groups", Msg::ReloadGroups, |groups| {
+        div![
+            class!["container"],
+            table![
+                class!["table"],
+                if endpoint::is_allowed::<CreateGroup>(user) {
+                    view_new_group(&model.new_group_name)
+                } else {
+                    empty![]
+                },
+                tbody![groups.iter().map(|group| tr![td![a![
+                    attrs! {At::Href => Route::Home(group.slug.clone()).to_string()},
+                    group.name,
+                ]]])],
+            ],
+        ]
+    })
+}
+
+fn view_new_group(new_group_name: &str) -> Node<Msg> {
+    thead![tr![td![input![
+        class!["input"],
+        attrs! {At::Placeholder => "New group", At::Value => new_group_name},
+        keyboard_ev(Ev::KeyDown, |keyboard_event| {
+            if keyboard_event.key_code() == ENTER_KEY {
+                Msg::CreateGroup
+            } else {
+                Msg::NoOp
+            }
+        }),
+        input_ev(Ev::Input, Msg::NewGroupNameChanged),
+    ]]]]
+}
@@ -0,0 +1,489 @@
+use super::{view_slides, ViewPage};
+use crate::config::Config;
+use crate::loading::{self, Loaded, Status};
+use crate::notification::{self, Notification};
+use crate::request::request;
+use crate::{route, GMsg, Route};
+use caliaconf_api::endpoint::{self, TalkHistory};
+use caliaconf_api::entity::{
+    talk, Employee, EmployeeId, ErrorMessage, GroupSlug, Talk, TalkId, TalkStatus, User,
+};
+use caliaconf_api::event::Event;
+use chrono::NaiveDate;
+use seed::{prelude::*, *};
+use std::collections::HashMap;
+
+const PAGE_SIZE: usize = 20;
+
+#[derive(Default)]
+pub struct Model {
+    group: GroupSlug,
+    /// Starts at 1.
+    page: u32,
+    history: Status<TalkHistory>,
+    filter: Filter,
+    drafts: HashMap<TalkId, Draft>,
+}
+
+#[derive(Default)]
+struct Filter {
+    person: Option<EmployeeId>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl Filter {
+    fn matches(&self, talk: &Talk) -> bool {
+        self.person.is_none_or(|person| talk.speaker_id == person)
+            && self.from.is_none_or(|from| talk.date >= from)
+            && self.to.is_none_or(|to| talk.date <= to)
+    }
+}
+
+/// Edits of a pending talk, not sent yet.
+#[derive(Default)]
+struct Draft {
+    title: Option<String>,
+    slides: Option<String>,
+    date: Option<String>,
+}
+
+pub fn init(
+    config: &Config,
+    group: GroupSlug,
+    page: u32,
+    person: Option<EmployeeId>,
+    orders: &mut impl Orders<Msg, GMsg>,
+) -> Model {
+    let mut model = Model {
+        group,
+        page,
+        filter: Filter {
+            person,
+            ..Filter::default()
+        },
+        ..Model::default()
+    };
+    load_history(&mut model, config, orders);
+    model
+}
+
+pub fn group(model: &Model) -> &GroupSlug {
+    &model.group
+}
+
+/// Moves to another page of the history without loading the talks again.
+pub fn change_page(model: &mut Model, page: u32, person: Option<EmployeeId>) {
+    model.page = page;
+    model.filter.person = person;
+}
+
+#[derive(Clone)]
+pub enum Msg {
+    HistoryLoaded(Loaded<TalkHistory>),
+    ReloadHistory,
+    PersonFilterChanged(String),
+    FromFilterChanged(String),
+    ToFilterChanged(String),
+    TitleChanged(TalkId, String),
+    SlidesChanged(TalkId, String),
+    DateChanged(TalkId, String),
+    ConfirmTalk(TalkId),
+    TalkConfirmed(Result<TalkHistory, Vec<ErrorMessage>>),
+    RescheduleTalk(TalkId),
+    TalkRescheduled(Result<TalkHistory, Vec<ErrorMessage>>),
+    CancelTalk(TalkId),
+    TalkCancelled(Result<TalkHistory, Vec<ErrorMessage>>),
+    EventReceived(Event),
+    SlowLoadThresholdPassed,
+}
+
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    match msg {
+        Msg::HistoryLoaded(result) => {
+            loading::finish(&mut model.history, result, "Loading talks", orders);
+        }
+        Msg::ReloadHistory => {
+            load_history(model, config, orders);
+        }
+        Msg::PersonFilterChanged(person) => {
+            route::go_to(
+                Route::History {
+                    group: model.group.clone(),
+                    page: 1,
+                    person: person.parse().ok(),
+                },
+                orders,
+            );
+        }
+        Msg::FromFilterChanged(date) => {
+            model.filter.from = date.parse().ok();
+        }
+        Msg::ToFilterChanged(date) => {
+            model.filter.to = date.parse().ok();
+        }
+        Msg::TitleChanged(talk_id, title) => {
+            model.drafts.entry(talk_id).or_default().title = Some(title);
+        }
+        Msg::SlidesChanged(talk_id, slides) => {
+            model.drafts.entry(talk_id).or_default().slides = Some(slides);
+        }
+        Msg::DateChanged(talk_id, date) => {
+            model.drafts.entry(talk_id).or_default().date = Some(date);
+        }
+        Msg::ConfirmTalk(talk_id) => {
+            let slides = model
+                .drafts
+                .get(&talk_id)
+                .and_then(|draft| draft.slides.as_deref())
+                .map(str::trim)
+                .filter(|slides| !slides.is_empty());
+            if slides.is_some_and(|slides| !talk::is_web_link(slides)) {
+                orders.send_g_msg(GMsg::Notify(Notification::warning(
+                    "The slides must be an http or https link",
+                )));
+                return;
+            }
+            // The draft is kept until the talk is confirmed, not to lose it if that fails.
+            let draft = model.drafts.get(&talk_id);
+            orders.skip().perform_cmd(request::confirm_talk(
+                config,
+                &model.group,
+                talk_id,
+                draft
+                    .and_then(|draft| draft.title.clone())
+                    .unwrap_or_default(),
+                draft.and_then(|draft| draft.slides.clone()),
+                Msg::TalkConfirmed,
+            ));
+        }
+        Msg::RescheduleTalk(talk_id) => {
+            let date = model
+                .drafts
+                .get(&talk_id)
+                .and_then(|draft| draft.date.as_ref())
+                .and_then(|date| date.parse().ok());
+            match date {
+                Some(date) => {
+                    model.drafts.remove(&talk_id);
+                    orders.skip().perform_cmd(request::reschedule_talk(
+                        config,
+                        &model.group,
+                        talk_id,
+                        date,
+                        Msg::TalkRescheduled,
+                    ));
+                }
+                None => {
+                    orders.send_g_msg(GMsg::Notify(Notification::warning(
+                        "Choose the new date first",
+                    )));
+                }
+            }
+        }
+        Msg::CancelTalk(talk_id) => {
+            orders.skip().perform_cmd(request::cancel_talk(
+                config,
+                &model.group,
+                talk_id,
+                Msg::TalkCancelled,
+            ));
+        }
+        Msg::TalkConfirmed(result) => {
+            talk_changed(result, "Talk confirmed", "Confirm", model, orders);
+        }
+        Msg::TalkRescheduled(result) => {
+            talk_changed(result, "Talk rescheduled", "Reschedule", model, orders);
+        }
+        Msg::TalkCancelled(result) => {
+            talk_changed(result, "Talk cancelled", "Cancel", model, orders);
+        }
+        Msg::EventReceived(event) => {
+            apply_event(&mut model.history, event, Msg::ReloadHistory, orders);
+            forget_settled_drafts(model);
+        }
+        Msg::SlowLoadThresholdPassed => {
+            model.history.slow_load_threshold_passed();
+        }
+    }
+}
+
+fn load_history(model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    let (config, group) = (config.clone(), model.group.clone());
+    loading::start(
+        &mut model.history,
+        move || request::load_talks(&config, &group),
+        Msg::HistoryLoaded,
+        Msg::SlowLoadThresholdPassed,
+        orders,
+    );
+}
+
+/// Applies the `event` to the talk `history` of a page,
+/// reloaded with `reload` when the event made talks it doesn't carry.
+pub fn apply_event<Ms: 'static>(
+    history: &mut Status<TalkHistory>,
+    event: Event,
+    reload: Ms,
+    orders: &mut impl Orders<Ms, GMsg>,
+) {
+    match event {
+        Event::RosterChanged(employees) | Event::RoundStarted((employees, _)) => {
+            if let Some((previous_employees, _)) = history.loaded_mut() {
+                *previous_employees = employees;
+            }
+        }
+        // The pick made a new pending talk.
+        Event::Picked(_) => {
+            orders.send_msg(reload);
+        }
+        Event::TalksChanged(changed) => *history = Status::Loaded(changed),
+        Event::AbsencesChanged(_) => (),
+    }
+}
+
+/// Only pending talks can be edited.
+fn forget_settled_drafts(model: &mut Model) {
+    if let Some((_, talks)) = model.history.loaded() {
+        model.drafts.retain(|talk_id, _| {
+            talks
+                .iter()
+                .any(|talk| talk.uuid == *talk_id && talk.status == TalkStatus::Pending)
+        });
+    }
+}
+
+fn talk_changed(
+    result: Result<TalkHistory, Vec<ErrorMessage>>,
+    success: &'static str,
+    action: &str,
+    model: &mut Model,
+    orders: &mut impl Orders<Msg, GMsg>,
+) {
+    match result {
+        Ok(history) => {
+            model.history = Status::Loaded(history);
+            forget_settled_drafts(model);
+            orders.send_g_msg(GMsg::Notify(Notification::success(success)));
+        }
+        Err(errors) => notification::notify_failure(action, &errors, orders),
+    }
+}
+
+/// Pending talks can only be edited by the users allowed to.
+pub fn view<'a>(model: &Model, user: Option<&User>) -> ViewPage<'a, Msg> {
+    ViewPage::new("History", view_content(model, user))
+}
+
+fn view_content(model: &Model, user: Option<&User>) -> Node<Msg> {
+    let editable = endpoint::is_allowed::<endpoint::Confirm>(user);
+    loading::view(
+        &model.history,
+        "talks",
+        Msg::ReloadHistory,
+        |(employees, talks)| {
+            let talks = talks
+                .iter()
+                .filter(|talk| model.filter.matches(talk))
+                .collect::<Vec<_>>();
+            let page_count = talks.len().div_ceil(PAGE_SIZE).max(1) as u32;
+            let page = model.page.min(page_count);
+
+            div![
+                class!["container"],
+                view_filter(&model.filter, employees),
+                table![
+                    class!["table is-fullwidth"],
+                    thead![tr![
+                        th!["Date"],
+                        th!["Speaker"],
+                        th!["Title"],
+                        th!["Slides"],
+                        th!["Status"],
+                    ]],
+                    tbody![talks
+                        .iter()
+                        .skip((page as usize - 1) * PAGE_SIZE)
+                        .take(PAGE_SIZE)
+                        .map(|talk| {
+                            let draft = model.drafts.get(&talk.uuid);
+                            view_talk(talk, employees, draft, editable)
+                        })],
+                ],
+                view_pagination(model, page, page_count),
+            ]
+        },
+    )
+}
+
+fn view_pagination(model: &Model, page: u32, page_count: u32) -> Node<Msg> {
+    if page_count == 1 {
+        return empty![];
+    }
+    // A disabled link has no target, so that it can't lead out of the pages.
+    let link = |page: u32, enabled: bool| {
+        if enabled {
+            let route = Route::History {
+                group: model.group.clone(),
+                page,
+                person: model.filter.person,
+            };
+            attrs! {At::Href => route.to_string()}
+        } else {
+            attrs! {At::Disabled => true.as_at_value()}
+        }
+    };
+
+    nav![
+        class!["pagination is-centered"],
+        a![
+            class!["pagination-previous"],
+            link(page - 1, page > 1),
+            "Previous",
+        ],
+        a![
+            class!["pagination-next"],
+            link(page + 1, page < page_count),
+            "Next",
+        ],
+        ul![
+            class!["pagination-list"],
+            li![span![format!("Page {} of {}", page, page_count)]],
+        ],
+    ]
+}
+
+fn view_filter(filter: &Filter, employees: &[Employee]) -> Node<Msg> {
+    let mut employees = employees.iter().collect::<Vec<_>>();
+    employees.sort_by(|a, b| a.name.cmp(&b.name));
+
+    div![
+        class!["field is-grouped"],
+        div![
+            class!["control"],
+            div![
+                class!["select"],
+                select![
+                    option![attrs! {At::Value => ""}, "Everybody"],
+                    employees.iter().map(|employee| option![
+                        attrs! {
+                            At::Value => employee.uuid,
+                            At::Selected => (filter.person == Some(employee.uuid)).as_at_value(),
+                        },
+                        employee.name,
+                    ]),
+                    input_ev(Ev::Change, Msg::PersonFilterChanged),
+                ],
+            ],
+        ],
+        div![
+            class!["control"],
+            input![
+                class!["input"],
+                attrs! {
+                    At::Type => "date",
+                    At::Title => "From",
+                    At::Value => filter.from.map(|date| date.to_string()).unwrap_or_default(),
+                },
+                input_ev(Ev::Input, Msg::FromFilterChanged),
+            ],
+        ],
+        div![
+            class!["control"],
+            input![
+                class!["input"],
+                attrs! {
+                    At::Type => "date",
+                    At::Title => "To",
+                    At::Value => filter.to.map(|date| date.to_string()).unwrap_or_default(),
+                },
+                input_ev(Ev::Input, Msg::ToFilterChanged),
+            ],
+        ],
+    ]
+}
+
+fn view_talk(
+    talk: &Talk,
+    employees: &[Employee],
+    draft: Option<&Draft>,
+    editable: bool,
+) -> Node<Msg> {
+    let speaker = employees
+        .iter()
+        .find(|employee| employee.uuid == talk.speaker_id)
+        .map_or("Former colleague", |employee| employee.name.as_str());
+
+    if talk.status == TalkStatus::Pending && editable {
+        return view_pending_talk(talk, speaker, draft);
+    }
+    tr![
+        td![talk.date.to_string()],
+        td![speaker],
+        td![talk.title],
+        td![view_slides(talk)],
+        td![match talk.status {
+            TalkStatus::Pending => span![class!["tag is-warning"], "Pending"],
+            TalkStatus::Confirmed => span![class!["tag is-success"], "Confirmed"],
+            TalkStatus::Cancelled => span![class!["tag"], "Cancelled"],
+        }],
+    ]
+}
+
+fn view_pending_talk(talk: &Talk, speaker: &str, draft: Option<&Draft>) -> Node<Msg> {
+    let talk_id = talk.uuid;
+    let title = draft.and_then(|draft| draft.title.as_deref());
+    let slides = draft.and_then(|draft| draft.slides.as_deref());
+    let date = draft
+        .and_then(|draft| draft.date.clone())
+        .unwrap_or_else(|| talk.date.to_string());
+
+    tr![
+        td![div![
+            class!["field has-addons"],
+            div![
+                class!["control"],
+                input![
+                    class!["input is-small"],
+                    attrs! {At::Type => "date", At::Value => date},
+                    input_ev(Ev::Input, move |date| Msg::DateChanged(talk_id, date)),
+                ],
+            ],
+            div![
+                class!["control"],
+                button![
+                    class!["button is-small"],
+                    simple_ev(Ev::Click, Msg::RescheduleTalk(talk_id)),
+                    "Reschedule",
+                ],
+            ],
+        ]],
+        td![speaker],
+        td![input![
+            class!["input is-small"],
+            attrs! {At::Placeholder => "Title", At::Value => title.unwrap_or(&talk.title)},
+            input_ev(Ev::Input, move |title| Msg::TitleChanged(talk_id, title)),
+        ]],
+        td![input![
+            class!["input is-small"],
+            attrs! {
+                At::Placeholder => "Link to the slides",
+                At::Value => slides.or(talk.slides.as_deref()).unwrap_or_default(),
+            },
+            input_ev(Ev::Input, move |slides| Msg::SlidesChanged(talk_id, slides)),
+        ]],
+        td![div![
+            class!["buttons"],
+            button![
+                class!["button is-small is-primary"],
+                simple_ev(Ev::Click, Msg::ConfirmTalk(talk_id)),
+                "Confirm",
+            ],
+            button![
+                class!["button is-small is-danger is-outlined"],
+                simple_ev(Ev::Click, Msg::CancelTalk(talk_id)),
+                "Cancel",
+            ],
+        ]],
+    ]
+}
@@ -1,121 +1,513 @@
 use super::ViewPage;
-use crate::entity::{Employee, ErrorMessage};
+use crate::config::Config;
+use crate::loading::{self, Loaded, Status};
+use crate::notification::{self, Notification};
+use crate::outbox::{self, Mutation};
+use crate::preferences::Preferences;
 use crate::request::request;
-use crate::{loading, logger, GMsg};
+use crate::{storage, GMsg, Route};
+use caliaconf_api::endpoint::{self, Picked, RoundState};
+use caliaconf_api::entity::{
+    absence, Absence, Employee, EmployeeId, ErrorMessage, GroupSlug, User,
+};
+use caliaconf_api::event::Event;
+use caliaconf_api::pick::PickStrategy;
+use chrono::{NaiveDate, Utc};
+use presentation::Presentation;
 use seed::{prelude::*, *};
 
+mod presentation;
+
 #[derive(Default)]
 pub struct Model {
-    employees: Status<Vec<Employee>>,
+    group: GroupSlug,
+    round: Status<RoundState>,
+    absences: Vec<Absence>,
     employee_picked: Option<Employee>,
+    /// Drawn but not accepted yet.
+    candidate: Option<Employee>,
+    /// Drawn before the candidate but unavailable, they are not drawn again.
+    rerolled: Vec<EmployeeId>,
+    pick_strategy: PickStrategy,
+    /// Shown full screen, for the meeting.
+    presentation: Option<Presentation>,
 }
 
-enum Status<T> {
-    Loading,
-    LoadingSlowly,
-    Loaded(T),
-    Failed,
-}
-
-impl<T> Default for Status<T> {
-    fn default() -> Self {
-        Self::Loading
-    }
-}
-
-pub fn init(orders: &mut impl Orders<Msg, GMsg>) -> Model {
-    orders
-        .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::load_list(Msg::ListLoaded));
+pub fn init(
+    config: &Config,
+    group: GroupSlug,
+    preferences: &Preferences,
+    orders: &mut impl Orders<Msg, GMsg>,
+) -> Model {
+    orders.perform_cmd(request::load_absences(config, &group, Msg::AbsencesLoaded));
 
-    Model::default()
+    let mut model = Model {
+        round: Status::new(storage::cached_round(&group)),
+        group,
+        pick_strategy: preferences.pick_strategy,
+        ..Model::default()
+    };
+    load_round(&mut model, config, orders);
+    model
 }
 
 #[derive(Clone)]
 pub enum Msg {
-    ListLoaded(Result<Vec<Employee>, Vec<ErrorMessage>>),
+    RoundLoaded(Loaded<RoundState>),
+    ReloadRound,
+    AbsencesLoaded(Result<Vec<Absence>, Vec<ErrorMessage>>),
     PickEmployee,
-    EmployeePicked(Result<(Vec<Employee>, Option<Employee>), Vec<ErrorMessage>>),
+    CandidateDrawn(Result<Option<Employee>, Vec<ErrorMessage>>),
+    AcceptCandidate,
+    RerollCandidate,
+    CancelPick,
+    EmployeePicked(Result<Picked, Vec<ErrorMessage>>),
+    EventReceived(Event),
+    EnterPresentation,
+    ExitPresentation,
+    PresentationKeyPressed(String),
+    AnimationFrame(Option<RenderTimestampDelta>),
+    ReducedMotionToggled,
     SlowLoadThresholdPassed,
 }
 
-pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
     match msg {
-        Msg::ListLoaded(Ok(employees)) => {
-            model.employees = Status::Loaded(employees);
+        Msg::RoundLoaded(result) => {
+            if let Ok(round) = &result {
+                storage::cache_round(&model.group, round);
+            }
+            loading::finish(&mut model.round, result, "Loading the round", orders);
+        }
+        Msg::ReloadRound => {
+            load_round(model, config, orders);
         }
-        Msg::ListLoaded(Err(errors)) => {
-            model.employees = Status::Failed;
-            logger::errors(errors);
+        Msg::AbsencesLoaded(Ok(absences)) => {
+            model.absences = absences;
+        }
+        Msg::AbsencesLoaded(Err(errors)) => {
+            notification::notify_failure("Loading absences", &errors, orders);
         }
         Msg::PickEmployee => {
-            orders
-                .skip()
-                .perform_cmd(request::pick_employee(Msg::EmployeePicked));
+            model.employee_picked = None;
+            model.rerolled.clear();
+            start_presentation_spin(model, orders);
+            draw_candidate(model, config, orders);
+        }
+        Msg::CandidateDrawn(Ok(candidate)) => {
+            if candidate.is_none() {
+                orders.send_g_msg(GMsg::Notify(Notification::warning(
+                    if model.rerolled.is_empty() {
+                        "Nobody to pick, add people in the settings"
+                    } else {
+                        "Nobody else is available"
+                    },
+                )));
+            }
+            if let Some(presentation) = &mut model.presentation {
+                match &candidate {
+                    Some(candidate) => presentation.land(&candidate.name),
+                    None => presentation.stop(),
+                }
+            }
+            model.candidate = candidate;
+        }
+        Msg::CandidateDrawn(Err(errors)) => {
+            if let Some(presentation) = &mut model.presentation {
+                presentation.stop();
+            }
+            model.candidate = None;
+            notification::notify_failure("Pick", &errors, orders);
+        }
+        Msg::AcceptCandidate if outbox::is_offline() => {
+            if let Some(candidate) = model.candidate.take() {
+                orders.send_g_msg(GMsg::Queue(Mutation::AcceptPick {
+                    group: model.group.clone(),
+                    employee_id: candidate.uuid,
+                    name: candidate.name.clone(),
+                }));
+                if let Some((employees, round)) = model.round.loaded_mut() {
+                    round.picked.push(candidate.uuid);
+                    for employee in employees
+                        .iter_mut()
+                        .filter(|employee| employee.uuid == candidate.uuid)
+                    {
+                        employee.picked = true;
+                    }
+                    storage::cache_round(&model.group, &(employees.clone(), round.clone()));
+                }
+                model.rerolled.clear();
+                model.employee_picked = Some(candidate);
+            }
+        }
+        Msg::AcceptCandidate => {
+            if let Some(candidate) = &model.candidate {
+                orders.skip().perform_cmd(request::accept_pick(
+                    config,
+                    &model.group,
+                    candidate.uuid,
+                    Msg::EmployeePicked,
+                ));
+            }
+        }
+        Msg::RerollCandidate => {
+            if let Some(candidate) = model.candidate.take() {
+                model.rerolled.push(candidate.uuid);
+            }
+            start_presentation_spin(model, orders);
+            draw_candidate(model, config, orders);
+        }
+        Msg::CancelPick => {
+            model.candidate = None;
+            model.rerolled.clear();
         }
         Msg::EmployeePicked(Ok((employees, employee))) => {
-            model.employees = Status::Loaded(employees);
-            model.employee_picked = employee;
+            if everybody_spoke(&employees) {
+                orders.send_g_msg(GMsg::Notify(Notification::info(
+                    "Everybody has spoken this round",
+                )));
+            }
+            if let Some((previous_employees, round)) = model.round.loaded_mut() {
+                // Already applied if the event of the pick came first.
+                if !round.picked.contains(&employee.uuid) {
+                    round.picked.push(employee.uuid);
+                }
+                *previous_employees = employees;
+                storage::cache_round(&model.group, &(previous_employees.clone(), round.clone()));
+            }
+            model.candidate = None;
+            model.rerolled.clear();
+            model.employee_picked = Some(employee);
         }
         Msg::EmployeePicked(Err(errors)) => {
-            model.employees = Status::Failed;
+            notification::notify_failure("Accept", &errors, orders);
+        }
+        Msg::EventReceived(
+            Event::RosterChanged(employees) | Event::TalksChanged((employees, _)),
+        ) => {
+            if let Some((previous_employees, round)) = model.round.loaded_mut() {
+                *previous_employees = employees;
+                storage::cache_round(&model.group, &(previous_employees.clone(), round.clone()));
+            }
+        }
+        Msg::EventReceived(Event::Picked((employees, employee))) => {
+            if let Some((previous_employees, round)) = model.round.loaded_mut() {
+                // Already applied if the pick was made from this page.
+                if !round.picked.contains(&employee.uuid) {
+                    round.picked.push(employee.uuid);
+                }
+                *previous_employees = employees;
+                storage::cache_round(&model.group, &(previous_employees.clone(), round.clone()));
+            }
+            if model.candidate.as_ref().map(|candidate| candidate.uuid) == Some(employee.uuid) {
+                model.candidate = None;
+                model.rerolled.clear();
+            }
+            model.employee_picked = Some(employee);
+        }
+        Msg::EventReceived(Event::RoundStarted(round)) => {
+            storage::cache_round(&model.group, &round);
+            model.round = Status::Loaded(round);
             model.employee_picked = None;
-            logger::errors(errors);
         }
-        Msg::SlowLoadThresholdPassed => {
-            if let Status::Loading = model.employees {
-                model.employees = Status::LoadingSlowly
+        Msg::EventReceived(Event::AbsencesChanged(absences)) => {
+            model.absences = absences;
+        }
+        Msg::EnterPresentation => {
+            model.presentation = Some(Presentation::new());
+        }
+        Msg::ExitPresentation => {
+            model.presentation = None;
+        }
+        Msg::PresentationKeyPressed(key) => {
+            let spinning = match &model.presentation {
+                Some(presentation) => presentation.is_spinning(),
+                None => return,
+            };
+            match key.as_str() {
+                " " if spinning => {
+                    orders.skip();
+                }
+                " " if model.candidate.is_some() => {
+                    orders.send_msg(Msg::RerollCandidate);
+                }
+                " " => {
+                    orders.send_msg(Msg::PickEmployee);
+                }
+                "Enter" if !spinning && model.candidate.is_some() => {
+                    orders.send_msg(Msg::AcceptCandidate);
+                }
+                "Escape" => {
+                    model.presentation = None;
+                }
+                _ => {
+                    orders.skip();
+                }
+            }
+        }
+        Msg::AnimationFrame(delta) => {
+            let delta = delta.map_or(0., f64::from);
+            if let Some(presentation) = &mut model.presentation {
+                if presentation.advance(delta) {
+                    orders.after_next_render(Msg::AnimationFrame);
+                }
             }
         }
+        Msg::ReducedMotionToggled => {
+            if let Some(presentation) = &mut model.presentation {
+                presentation.reduced_motion = !presentation.reduced_motion;
+                if presentation.is_spinning() {
+                    orders.after_next_render(Msg::AnimationFrame);
+                }
+            }
+        }
+        Msg::SlowLoadThresholdPassed => {
+            model.round.slow_load_threshold_passed();
+        }
     }
 }
 
-pub fn view<'a, Ms>(model: &Model) -> ViewPage<'a, Msg> {
-    ViewPage::new("Home", view_content(model))
+fn load_round(model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    let (config, group) = (config.clone(), model.group.clone());
+    loading::start(
+        &mut model.round,
+        move || request::load_round(&config, &group),
+        Msg::RoundLoaded,
+        Msg::SlowLoadThresholdPassed,
+        orders,
+    );
 }
 
-fn view_content(model: &Model) -> Node<Msg> {
-    match &model.employees {
-        Status::Loading => empty![],
-        Status::LoadingSlowly => loading::view_icon(),
-        Status::Failed => loading::view_error("employees"),
-        Status::Loaded(employees) => {
-            let filter_fn: Box<dyn Fn(&Employee) -> bool> =
-                if employees.iter().all(|employee| employee.picked) {
-                    Box::new(|_| true)
-                } else {
-                    Box::new(|employee: &Employee| !employee.picked)
-                };
+/// Presentation keys, while it's shown.
+pub fn window_events(model: &Model) -> Vec<EventHandler<Msg>> {
+    if model.presentation.is_none() {
+        return Vec::new();
+    }
+    vec![keyboard_ev(Ev::KeyDown, |event| {
+        if event.key() == " " {
+            // Would scroll the page, or click the focused button.
+            event.prevent_default();
+        }
+        Msg::PresentationKeyPressed(event.key())
+    })]
+}
+
+/// Shuffles the names of the people who can be drawn while the server draws one of them.
+fn start_presentation_spin(model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    let today = absence::today();
+    let names = match model.round.loaded() {
+        Some((employees, _)) => employees
+            .iter()
+            .filter(|employee| !employee.inactive && !employee.picked)
+            .filter(|employee| !model.rerolled.contains(&employee.uuid))
+            .filter(|employee| return_date(employee, &model.absences, today).is_none())
+            .map(|employee| employee.name.clone())
+            .collect(),
+        None => Vec::new(),
+    };
+    if let Some(presentation) = &mut model.presentation {
+        presentation.start(names);
+        orders.after_next_render(Msg::AnimationFrame);
+    }
+}
+
+fn draw_candidate(model: &Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    if outbox::is_offline() {
+        orders.send_msg(Msg::CandidateDrawn(Ok(draw_offline(model))));
+        return;
+    }
+    orders.skip().perform_cmd(request::draw_employee(
+        config,
+        &model.group,
+        model.pick_strategy,
+        model.rerolled.clone(),
+        Msg::CandidateDrawn,
+    ));
+}
+
+/// Draws among the round loaded last, like the server does.
+/// The server checks the pick once it's sent.
+fn draw_offline(model: &Model) -> Option<Employee> {
+    let (employees, _) = model.round.loaded()?;
+    let today = absence::today();
+    let (picked, candidates): (Vec<_>, Vec<_>) = employees
+        .iter()
+        .filter(|employee| !employee.inactive && !model.rerolled.contains(&employee.uuid))
+        .filter(|employee| {
+            employee.picked || return_date(employee, &model.absences, today).is_none()
+        })
+        .partition(|employee| employee.picked);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let random = (js_sys::Math::random() * u64::MAX as f64) as u64;
+    model
+        .pick_strategy
+        .choose(&candidates, Utc::now(), random, picked.len())
+        .cloned()
+}
+
+fn everybody_spoke(employees: &[Employee]) -> bool {
+    let mut active = employees.iter().filter(|employee| !employee.inactive);
+    active.clone().next().is_some() && active.all(|employee| employee.picked)
+}
+
+/// Only the users allowed to pick see the picking controls.
+pub fn view<'a>(model: &Model, user: Option<&User>) -> ViewPage<'a, Msg> {
+    ViewPage::new("Home", view_content(model, user))
+}
+
+fn view_content(model: &Model, user: Option<&User>) -> Node<Msg> {
+    let can_pick = endpoint::is_allowed::<endpoint::Draw>(user)
+        && endpoint::is_allowed::<endpoint::Accept>(user);
+    let settings = Route::settings(model.group.clone());
+    loading::view(
+        &model.round,
+        "round",
+        Msg::ReloadRound,
+        |(employees, round)| {
             div![
-                class!["columns is-vcentered"],
-                table![
-                    class!["table column"],
-                    thead![tr![th!["They can be the next :D"],],],
-                    tbody![employees.iter().filter_map(|employee| {
-                        if filter_fn(employee) {
-                            Some(tr![td![employee.name]])
-                        } else {
-                            None
-                        }
-                    })],
-                ],
-                button![
-                    class!["button is-large is-primary column"],
-                    simple_ev(Ev::Click, Msg::PickEmployee),
-                    "Pick the next!"
-                ],
+                view_progress(employees, round.number),
+                if can_pick {
+                    div![
+                        class!["container"],
+                        button![
+                            class!["button is-small"],
+                            simple_ev(Ev::Click, Msg::EnterPresentation),
+                            span![class!["icon"], i![class!["fas fa-tv"]]],
+                            span!["Presentation mode"],
+                        ],
+                    ]
+                } else {
+                    empty![]
+                },
+                match &model.presentation {
+                    Some(presentation) => presentation::view(
+                        presentation,
+                        model.candidate.as_ref(),
+                        model.employee_picked.as_ref(),
+                    ),
+                    None => empty![],
+                },
                 div![
-                    class!["column"],
-                    match &model.employee_picked {
-                        Some(employee) => {
-                            span!["Congratulations ", b![employee.name], ". You are the next!",]
+                    class!["columns is-vcentered"],
+                    table![
+                        class!["table column"],
+                        thead![tr![th!["They can be the next :D"],],],
+                        tbody![employees
+                            .iter()
+                            .filter(|employee| !employee.picked && !employee.inactive)
+                            .map(|employee| view_employee(
+                                &model.group,
+                                employee,
+                                &model.absences
+                            ))],
+                    ],
+                    match &model.candidate {
+                        _ if !can_pick => empty![],
+                        Some(candidate) => view_candidate(candidate),
+                        None => button![
+                            class!["button is-large is-primary column"],
+                            attrs! {At::Disabled => everybody_spoke(employees).as_at_value()},
+                            simple_ev(Ev::Click, Msg::PickEmployee),
+                            "Pick the next!"
+                        ],
+                    },
+                    div![
+                        class!["column"],
+                        match &model.employee_picked {
+                            Some(employee) => {
+                                span!["Congratulations ", b![employee.name], ". You are the next!",]
+                            }
+                            None if everybody_spoke(employees) && settings.is_allowed(user) => {
+                                span![
+                                    "Everybody has spoken, start a new round in the ",
+                                    a![attrs! {At::Href => settings.to_string()}, "settings"],
+                                    ".",
+                                ]
+                            }
+                            None if everybody_spoke(employees) => span!["Everybody has spoken."],
+                            None => {
+                                empty![]
+                            }
                         }
-                        None => {
-                            empty![]
-                        }
-                    }
+                    ]
                 ]
             ]
-        }
+        },
+    )
+}
+
+fn view_employee(group: &GroupSlug, employee: &Employee, absences: &[Absence]) -> Node<Msg> {
+    let route = Route::Employee(group.clone(), employee.uuid);
+    let name = a![attrs! {At::Href => route.to_string()}, employee.name];
+    match return_date(employee, absences, absence::today()) {
+        Some(return_date) => tr![
+            class!["has-text-grey-light"],
+            td![
+                name,
+                span![
+                    class!["is-italic"],
+                    format!(" (absent, back on {})", return_date)
+                ],
+            ],
+        ],
+        None => tr![td![name]],
     }
 }
+
+/// When the `employee` is absent on the `date`, the day they are back.
+fn return_date(employee: &Employee, absences: &[Absence], date: NaiveDate) -> Option<NaiveDate> {
+    absences
+        .iter()
+        .filter(|absence| absence.employee_id == employee.uuid && absence.covers(date))
+        .map(Absence::return_date)
+        .max()
+}
+
+fn view_candidate(candidate: &Employee) -> Node<Msg> {
+    div![
+        class!["column has-text-centered"],
+        p![class!["title"], candidate.name],
+        div![
+            class!["buttons is-centered"],
+            button![
+                class!["button is-primary"],
+                simple_ev(Ev::Click, Msg::AcceptCandidate),
+                "Accept"
+            ],
+            button![
+                class!["button is-warning"],
+                simple_ev(Ev::Click, Msg::RerollCandidate),
+                "Re-roll (person unavailable)"
+            ],
+            button![
+                class!["button"],
+                simple_ev(Ev::Click, Msg::CancelPick),
+                "Cancel"
+            ],
+        ],
+    ]
+}
+
+fn view_progress(employees: &[Employee], round_number: u32) -> Node<Msg> {
+    let employees = employees
+        .iter()
+        .filter(|employee| !employee.inactive)
+        .collect::<Vec<_>>();
+    let spoken = employees.iter().filter(|employee| employee.picked).count();
+
+    div![
+        class!["container"],
+        p![
+            b![format!("Round {}", round_number)],
+            format!(
+                " - {} of {} have spoken this round",
+                spoken,
+                employees.len()
+            ),
+        ],
+        progress![
+            class!["progress is-primary"],
+            attrs! {At::Value => spoken, At::Max => employees.len().max(1)},
+        ],
+    ]
+}
@@ -0,0 +1,193 @@
+//! Full-screen mode of the home page for the meeting: the names are shuffled like a slot machine
+//! before landing on the person the server drew.
+
+use super::Msg;
+use caliaconf_api::entity::Employee;
+use seed::{prelude::*, *};
+
+/// Time between two names at full speed, in ms.
+const FAST_INTERVAL: f64 = 60.;
+/// Time between the last two names, in ms.
+const SLOW_INTERVAL: f64 = 500.;
+/// Shortest shuffle before slowing down, even if the server answers right away, in ms.
+const MIN_SPIN: f64 = 1_500.;
+/// Names shown while slowing down, the last one being the winner.
+const LANDING_SWITCHES: usize = 16;
+
+// ------ Presentation ------
+
+pub struct Presentation {
+    /// Shuffled names.
+    names: Vec<String>,
+    /// Index of the name shown.
+    shown: usize,
+    spin: Option<Spin>,
+    /// The winner is shown right away, without shuffling.
+    pub reduced_motion: bool,
+}
+
+/// The names being shuffled.
+#[derive(Default)]
+struct Spin {
+    /// Since the start of the shuffle, in ms.
+    elapsed: f64,
+    /// Since the last name switch, in ms.
+    since_switch: f64,
+    /// Index of the winner, once the server has drawn them.
+    winner: Option<usize>,
+    /// Switches left before landing on the winner, once slowing down.
+    switches_left: Option<usize>,
+}
+
+impl Presentation {
+    /// Follows `prefers-reduced-motion`.
+    pub fn new() -> Self {
+        let reduced_motion = seed::window()
+            .match_media("(prefers-reduced-motion: reduce)")
+            .ok()
+            .flatten()
+            .is_some_and(|query| query.matches());
+        Self {
+            names: Vec::new(),
+            shown: 0,
+            spin: None,
+            reduced_motion,
+        }
+    }
+
+    /// Starts shuffling the `names`, until `land` is called.
+    pub fn start(&mut self, names: Vec<String>) {
+        self.names = names;
+        self.shown = 0;
+        self.spin = Some(Spin::default());
+    }
+
+    /// Slows down to stop on `name`.
+    pub fn land(&mut self, name: &str) {
+        let winner = match self.names.iter().position(|other| other == name) {
+            Some(winner) => winner,
+            None => {
+                self.names.push(name.to_owned());
+                self.names.len() - 1
+            }
+        };
+        match &mut self.spin {
+            Some(spin) if !self.reduced_motion => spin.winner = Some(winner),
+            _ => self.stop(),
+        }
+    }
+
+    /// Stops shuffling, nobody was drawn.
+    pub fn stop(&mut self) {
+        self.spin = None;
+    }
+
+    pub fn is_spinning(&self) -> bool {
+        self.spin.is_some()
+    }
+
+    /// Moves the shuffle `delta` ms forward, returns whether it goes on.
+    pub fn advance(&mut self, delta: f64) -> bool {
+        let spin = match &mut self.spin {
+            Some(spin) if !self.reduced_motion => spin,
+            _ => return false,
+        };
+        spin.elapsed += delta;
+        spin.since_switch += delta;
+
+        if let (Some(winner), None) = (spin.winner, spin.switches_left) {
+            if spin.elapsed >= MIN_SPIN {
+                // Enough switches for the last one to show the winner.
+                let count = self.names.len();
+                let distance = (winner + count - self.shown) % count;
+                let turns = LANDING_SWITCHES.saturating_sub(distance).div_ceil(count);
+                spin.switches_left = Some(distance + count * turns);
+            }
+        }
+
+        let interval = match spin.switches_left {
+            // Eases out, from full speed to the slowest.
+            #[allow(clippy::cast_precision_loss)]
+            Some(switches_left) => {
+                let progress = 1. - switches_left as f64 / LANDING_SWITCHES.max(1) as f64;
+                FAST_INTERVAL + (SLOW_INTERVAL - FAST_INTERVAL) * progress.max(0.).powi(2)
+            }
+            None => FAST_INTERVAL,
+        };
+        if spin.since_switch < interval {
+            return true;
+        }
+        spin.since_switch = 0.;
+
+        match spin.switches_left {
+            Some(0) => {
+                self.shown = spin.winner.unwrap_or(self.shown);
+                self.spin = None;
+                false
+            }
+            Some(switches_left) => {
+                spin.switches_left = Some(switches_left - 1);
+                self.shown = (self.shown + 1) % self.names.len().max(1);
+                true
+            }
+            None => {
+                self.shown = (self.shown + 1) % self.names.len().max(1);
+                true
+            }
+        }
+    }
+}
+
+// ------ view ------
+
+/// The `candidate` is shown once the shuffle has landed on them, then the `picked` person once accepted.
+pub fn view(
+    presentation: &Presentation,
+    candidate: Option<&Employee>,
+    picked: Option<&Employee>,
+) -> Node<Msg> {
+    let (name, hint) = match (presentation.is_spinning(), candidate, picked) {
+        (true, _, _) if presentation.reduced_motion => (None, "And the next is..."),
+        (true, _, _) => (
+            presentation.names.get(presentation.shown).cloned(),
+            "And the next is...",
+        ),
+        (false, Some(candidate), _) => (
+            Some(candidate.name.clone()),
+            "Enter to accept, Space to draw someone else",
+        ),
+        (false, None, Some(picked)) => (
+            Some(format!("Congratulations {}!", picked.name)),
+            "Space to pick the next one",
+        ),
+        (false, None, None) => (None, "Space to pick the next speaker"),
+    };
+
+    div![
+        class!["presentation"],
+        button![
+            class!["delete is-large"],
+            attrs! {At::Title => "Exit (Esc)"},
+            simple_ev(Ev::Click, Msg::ExitPresentation),
+        ],
+        p![
+            class![
+                "presentation-name",
+                "is-landed" => !presentation.is_spinning(),
+            ],
+            name.unwrap_or_else(|| "?".to_owned()),
+        ],
+        p![class!["subtitle"], hint],
+        label![
+            class!["checkbox"],
+            input![
+                attrs! {
+                    At::Type => "checkbox",
+                    At::Checked => presentation.reduced_motion.as_at_value(),
+                },
+                simple_ev(Ev::Change, Msg::ReducedMotionToggled),
+            ],
+            " Reduce motion",
+        ],
+    ]
+}
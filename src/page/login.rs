@@ -0,0 +1,112 @@
+use super::ViewPage;
+use crate::config::Config;
+use crate::notification::Notification;
+use crate::request::request;
+use crate::GMsg;
+use caliaconf_api::endpoint::{Credentials, Session};
+use caliaconf_api::entity::ErrorMessage;
+use seed::{prelude::*, *};
+
+#[derive(Default)]
+pub struct Model {
+    name: String,
+    password: String,
+    /// The credentials have been sent, their answer is awaited.
+    logging_in: bool,
+}
+
+pub fn init() -> Model {
+    Model::default()
+}
+
+#[derive(Clone)]
+pub enum Msg {
+    NameChanged(String),
+    PasswordChanged(String),
+    LogIn,
+    LoggedIn(Result<Session, Vec<ErrorMessage>>),
+}
+
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    match msg {
+        Msg::NameChanged(name) => {
+            model.name = name;
+        }
+        Msg::PasswordChanged(password) => {
+            model.password = password;
+        }
+        Msg::LogIn => {
+            if model.logging_in {
+                return;
+            }
+            model.logging_in = true;
+            let credentials = Credentials {
+                name: model.name.trim().to_owned(),
+                password: model.password.clone(),
+            };
+            orders.perform_cmd(request::login(config, credentials, Msg::LoggedIn));
+        }
+        Msg::LoggedIn(Ok(session)) => {
+            model.password.clear();
+            model.logging_in = false;
+            orders.send_g_msg(GMsg::LoggedIn(session));
+        }
+        Msg::LoggedIn(Err(errors)) => {
+            model.logging_in = false;
+            // Not `notify_failure`, which would take the 401 of wrong credentials for an expired session.
+            for error in errors {
+                orders.send_g_msg(GMsg::Notify(Notification::failure("Log in", &error)));
+            }
+        }
+    }
+}
+
+pub fn view<'a>(model: &Model) -> ViewPage<'a, Msg> {
+    ViewPage::new("Log in", view_content(model))
+}
+
+fn view_content(model: &Model) -> Node<Msg> {
+    div![
+        class!["container"],
+        h1![class!["title"], "Log in"],
+        form![
+            ev(Ev::Submit, |event| {
+                event.prevent_default();
+                Msg::LogIn
+            }),
+            div![
+                class!["field"],
+                label![class!["label"], "Name"],
+                div![
+                    class!["control"],
+                    input![
+                        class!["input"],
+                        attrs! {At::Value => model.name, At::AutoComplete => "username"},
+                        input_ev(Ev::Input, Msg::NameChanged),
+                    ],
+                ],
+            ],
+            div![
+                class!["field"],
+                label![class!["label"], "Password"],
+                div![
+                    class!["control"],
+                    input![
+                        class!["input"],
+                        attrs! {
+                            At::Type => "password",
+                            At::Value => model.password,
+                            At::AutoComplete => "current-password",
+                        },
+                        input_ev(Ev::Input, Msg::PasswordChanged),
+                    ],
+                ],
+            ],
+            button![
+                class!["button is-primary", "is-loading" => model.logging_in],
+                attrs! {At::Type => "submit"},
+                "Log in",
+            ],
+        ],
+    ]
+}
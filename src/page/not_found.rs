@@ -1,4 +1,5 @@
 use super::ViewPage;
+use crate::image::Image;
 use seed::{prelude::*, *};
 
 // ------ ------
@@ -19,7 +20,7 @@ fn view_content<Ms>() -> Node<Ms> {
         h1!["Not Found"],
         div![
             class!["row"],
-            img![attrs! {At::Src => "Image::error().url()"}]
+            img![attrs! {At::Src => Image::error().url(), At::Alt => "Not found"}]
         ]
     ]
 }
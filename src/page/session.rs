@@ -0,0 +1,407 @@
+use super::ViewPage;
+use crate::config::Config;
+use crate::loading::{self, Loaded, Status};
+use crate::logger;
+use crate::request::request;
+use crate::timer::{self, Level, Stage, Timer, TimerState};
+use crate::{storage, GMsg, Route};
+use caliaconf_api::endpoint::RoundState;
+use caliaconf_api::entity::{Employee, EmployeeId, GroupSlug};
+use caliaconf_api::event::Event;
+use gloo_timers::future::TimeoutFuture;
+use seed::{prelude::*, *};
+use web_sys::AudioContext;
+
+/// Time between two refreshes of the running timer, in ms.
+const TICK: u32 = 250;
+
+#[derive(Default)]
+pub struct Model {
+    group: GroupSlug,
+    round: Status<RoundState>,
+    stage: Stage,
+    /// Seconds left at the last tick, to ring the warnings crossed since.
+    last_remaining: i64,
+    /// A tick is awaited, there is only one at a time.
+    ticking: bool,
+    /// Created on the first start or when the sound is enabled, browsers only play sounds once
+    /// the page has been clicked.
+    audio: Option<AudioContext>,
+    duration_input: String,
+    warnings_input: String,
+}
+
+pub fn init(config: &Config, group: GroupSlug, orders: &mut impl Orders<Msg, GMsg>) -> Model {
+    let stage = storage::load_stage(&group);
+    let mut model = Model {
+        round: Status::new(storage::cached_round(&group)),
+        last_remaining: stage.timer.remaining(timer::now()),
+        duration_input: (stage.timer.duration / 60).to_string(),
+        warnings_input: minutes(&stage.timer.warnings),
+        stage,
+        group,
+        ..Model::default()
+    };
+    load_round(&mut model, config, orders);
+    // Started before the page was reloaded.
+    if model.stage.timer.is_running() {
+        tick(&mut model, orders);
+    }
+    model
+}
+
+#[derive(Clone)]
+pub enum Msg {
+    RoundLoaded(Loaded<RoundState>),
+    ReloadRound,
+    EventReceived(Event),
+    Start,
+    Pause,
+    Reset,
+    NextSpeaker,
+    Tick,
+    EnableSound,
+    DurationChanged(String),
+    WarningsChanged(String),
+    SlowLoadThresholdPassed,
+}
+
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    match msg {
+        Msg::RoundLoaded(result) => {
+            if let Ok(round) = &result {
+                storage::cache_round(&model.group, round);
+                if model.stage.speaker.is_none() {
+                    model.stage.speaker = round.1.picked.last().copied();
+                    save_stage(model);
+                }
+            }
+            loading::finish(&mut model.round, result, "Loading the round", orders);
+        }
+        Msg::ReloadRound => {
+            load_round(model, config, orders);
+        }
+        Msg::EventReceived(
+            Event::RosterChanged(employees) | Event::TalksChanged((employees, _)),
+        ) => {
+            if let Some((previous_employees, round)) = model.round.loaded_mut() {
+                // Unpicked, or their talk was cancelled.
+                round.picked.retain(|uuid| {
+                    employees
+                        .iter()
+                        .any(|employee| employee.uuid == *uuid && employee.picked)
+                });
+                *previous_employees = employees;
+            }
+        }
+        Msg::EventReceived(Event::Picked((employees, employee))) => {
+            if let Some((previous_employees, round)) = model.round.loaded_mut() {
+                if !round.picked.contains(&employee.uuid) {
+                    round.picked.push(employee.uuid);
+                }
+                *previous_employees = employees;
+            }
+            if model.stage.speaker.is_none() {
+                model.stage.speaker = Some(employee.uuid);
+                save_stage(model);
+            }
+        }
+        Msg::EventReceived(Event::RoundStarted(round)) => {
+            model.round = Status::Loaded(round);
+        }
+        Msg::EventReceived(Event::AbsencesChanged(_)) => (),
+        Msg::Start => {
+            enable_sound(model);
+            let now = timer::now();
+            if model.stage.timer.state == TimerState::Stopped {
+                model.last_remaining = model.stage.timer.remaining(now);
+            }
+            model.stage.timer.start(now);
+            save_stage(model);
+            tick(model, orders);
+        }
+        Msg::Pause => {
+            model.stage.timer.pause(timer::now());
+            save_stage(model);
+        }
+        Msg::Reset => {
+            model.stage.timer.reset();
+            save_stage(model);
+        }
+        Msg::NextSpeaker => {
+            let next = model
+                .round
+                .loaded()
+                .and_then(|(_, round)| queue(&round.picked, model.stage.speaker).first().copied());
+            if let Some(next) = next {
+                model.stage.speaker = Some(next);
+                model.stage.timer.reset();
+                save_stage(model);
+            }
+        }
+        Msg::Tick => {
+            model.ticking = false;
+            if !model.stage.timer.is_running() {
+                return;
+            }
+            let remaining = model.stage.timer.remaining(timer::now());
+            let beeps = model.stage.timer.beeps(model.last_remaining, remaining);
+            if let (Some(audio), true) = (&model.audio, beeps > 0) {
+                if let Err(error) = timer::ring(audio, beeps) {
+                    logger::error(error);
+                }
+            }
+            model.last_remaining = remaining;
+            tick(model, orders);
+        }
+        Msg::EnableSound => enable_sound(model),
+        Msg::DurationChanged(duration) => {
+            if let Some(seconds) = duration
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|minutes| *minutes > 0)
+                .and_then(|minutes| minutes.checked_mul(60))
+            {
+                model.stage.timer.duration = seconds;
+                save_stage(model);
+            }
+            model.duration_input = duration;
+        }
+        Msg::WarningsChanged(warnings) => {
+            let parsed = warnings
+                .split(',')
+                .map(str::trim)
+                .filter(|minutes| !minutes.is_empty())
+                .map(|minutes| minutes.parse::<u32>().ok()?.checked_mul(60))
+                .collect::<Option<Vec<_>>>();
+            if let Some(parsed) = parsed {
+                model.stage.timer.set_warnings(parsed);
+                save_stage(model);
+            }
+            model.warnings_input = warnings;
+        }
+        Msg::SlowLoadThresholdPassed => {
+            model.round.slow_load_threshold_passed();
+        }
+    }
+}
+
+/// Creates the `audio`, to be called on a click.
+fn enable_sound(model: &mut Model) {
+    if model.audio.is_none() {
+        model.audio = AudioContext::new().map_err(logger::error).ok();
+    }
+}
+
+fn load_round(model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    let (config, group) = (config.clone(), model.group.clone());
+    loading::start(
+        &mut model.round,
+        move || request::load_round(&config, &group),
+        Msg::RoundLoaded,
+        Msg::SlowLoadThresholdPassed,
+        orders,
+    );
+}
+
+fn tick(model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    if model.ticking {
+        return;
+    }
+    model.ticking = true;
+    orders.perform_cmd(async {
+        TimeoutFuture::new(TICK).await;
+        Ok::<_, Msg>(Msg::Tick)
+    });
+}
+
+fn save_stage(model: &Model) {
+    storage::save_stage(&model.group, &model.stage);
+}
+
+/// The people picked after the `speaker`, in the order they were picked.
+fn queue(picked: &[EmployeeId], speaker: Option<EmployeeId>) -> &[EmployeeId] {
+    match speaker.and_then(|speaker| picked.iter().position(|id| *id == speaker)) {
+        Some(position) => &picked[position + 1..],
+        // The speaker was picked in a previous round.
+        None if speaker.is_some() => picked,
+        None => &[],
+    }
+}
+
+/// E.g. "5, 1" for warnings at 300 and 60 seconds.
+fn minutes(seconds: &[u32]) -> String {
+    seconds
+        .iter()
+        .map(|seconds| (seconds / 60).to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn view<'a>(model: &Model) -> ViewPage<'a, Msg> {
+    ViewPage::new("Session", view_content(model))
+}
+
+fn view_content(model: &Model) -> Node<Msg> {
+    loading::view(
+        &model.round,
+        "round",
+        Msg::ReloadRound,
+        |(employees, round)| {
+            let employee = |id: &EmployeeId| employees.iter().find(|employee| employee.uuid == *id);
+            let speaker = model.stage.speaker.as_ref().and_then(employee);
+            let queue = queue(&round.picked, model.stage.speaker)
+                .iter()
+                .filter_map(employee)
+                .collect::<Vec<_>>();
+
+            div![
+                class!["container"],
+                match speaker {
+                    Some(speaker) => h1![class!["title has-text-centered"], speaker.name],
+                    None => p![
+                        "Nobody has been picked this round yet, pick the speaker on the ",
+                        a![
+                            attrs! {At::Href => Route::Home(model.group.clone()).to_string()},
+                            "home page"
+                        ],
+                        ".",
+                    ],
+                },
+                view_timer(&model.stage.timer, model.audio.is_some()),
+                view_queue(&queue),
+                view_timer_settings(model),
+            ]
+        },
+    )
+}
+
+/// Started before the page was reloaded, the timer can't ring until the sound is enabled.
+fn view_timer(timer: &Timer, sound: bool) -> Node<Msg> {
+    let now = timer::now();
+    let remaining = timer.remaining(now);
+    let level = timer.level(now);
+    let (text_class, progress_class) = match level {
+        Level::Normal => ("has-text-success", "is-success"),
+        Level::Warning => ("has-text-warning", "is-warning"),
+        Level::Critical | Level::Overtime => ("has-text-danger", "is-danger"),
+    };
+    let elapsed = i64::from(timer.duration) - remaining;
+
+    div![
+        class![
+            "box has-text-centered",
+            "timer-alarm" => level == Level::Overtime && timer.is_running(),
+        ],
+        p![
+            class!["timer", text_class],
+            if remaining < 0 {
+                format!("+{}:{:02}", -remaining / 60, -remaining % 60)
+            } else {
+                format!("{}:{:02}", remaining / 60, remaining % 60)
+            },
+        ],
+        progress![
+            class!["progress", progress_class],
+            attrs! {
+                At::Value => elapsed.min(i64::from(timer.duration)),
+                At::Max => timer.duration.max(1),
+            },
+        ],
+        div![
+            class!["buttons is-centered"],
+            match timer.state {
+                TimerState::Running { .. } => button![
+                    class!["button is-warning"],
+                    simple_ev(Ev::Click, Msg::Pause),
+                    "Pause"
+                ],
+                TimerState::Paused { .. } => button![
+                    class!["button is-primary"],
+                    simple_ev(Ev::Click, Msg::Start),
+                    "Resume"
+                ],
+                TimerState::Stopped => button![
+                    class!["button is-primary"],
+                    simple_ev(Ev::Click, Msg::Start),
+                    "Start"
+                ],
+            },
+            button![class!["button"], simple_ev(Ev::Click, Msg::Reset), "Reset"],
+            if timer.is_running() && !sound {
+                button![
+                    class!["button is-light"],
+                    simple_ev(Ev::Click, Msg::EnableSound),
+                    span![class!["icon"], i![class!["fas fa-volume-mute"]]],
+                    span!["Sound off, click to enable"],
+                ]
+            } else {
+                empty![]
+            },
+        ],
+    ]
+}
+
+fn view_queue(queue: &[&Employee]) -> Node<Msg> {
+    match queue.split_first() {
+        Some((next, others)) => div![
+            class!["level"],
+            div![
+                class!["level-left"],
+                div![
+                    class!["level-item"],
+                    p![
+                        "Next: ",
+                        b![next.name],
+                        if others.is_empty() {
+                            empty![]
+                        } else {
+                            span![format!(
+                                ", then {}",
+                                others
+                                    .iter()
+                                    .map(|employee| employee.name.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )]
+                        },
+                    ],
+                ],
+            ],
+            div![
+                class!["level-right"],
+                button![
+                    class!["button is-info level-item"],
+                    simple_ev(Ev::Click, Msg::NextSpeaker),
+                    "Next speaker"
+                ],
+            ],
+        ],
+        None => p!["Nobody else is queued, the next picks will show up here."],
+    }
+}
+
+fn view_timer_settings(model: &Model) -> Node<Msg> {
+    div![
+        class!["columns"],
+        div![
+            class!["column field"],
+            label![class!["label"], "Talk length (minutes)"],
+            input![
+                class!["input"],
+                attrs! {At::Type => "number", At::Min => 1, At::Value => model.duration_input},
+                input_ev(Ev::Input, Msg::DurationChanged),
+            ],
+        ],
+        div![
+            class!["column field"],
+            label![class!["label"], "Warn when that many minutes are left"],
+            input![
+                class!["input"],
+                attrs! {At::Placeholder => "5, 1", At::Value => model.warnings_input},
+                input_ev(Ev::Input, Msg::WarningsChanged),
+            ],
+        ],
+    ]
+}
@@ -1,120 +1,851 @@
 use super::ViewPage;
-use crate::entity::{Employee, EmployeeId, ErrorMessage};
+use crate::config::Config;
+use crate::loading::{self, Loaded, Status};
+use crate::notification::{self, Notification};
+use crate::outbox::{self, Mutation};
+use crate::preferences::Preferences;
 use crate::request::request;
-use crate::{entity, loading, logger, GMsg};
-use enclose::enc;
+use crate::route::SettingsTab;
+use crate::{file, logger, roster, storage, GMsg, Route};
+use caliaconf_api::endpoint::{RoundState, UpdateEmployee};
+use caliaconf_api::entity::{Absence, AbsenceId, Employee, EmployeeId, ErrorMessage, GroupSlug};
+use caliaconf_api::event::Event;
+use caliaconf_api::pick::PickStrategy;
 use seed::{prelude::*, *};
 
 const ENTER_KEY: u32 = 13;
 
 #[derive(Default)]
 pub struct Model {
+    group: GroupSlug,
+    tab: SettingsTab,
     employees: Status<Vec<Employee>>,
     new_employee_name: String,
+    /// Roster to import, pasted or read from a file.
+    import: String,
+    pick_strategy: PickStrategy,
+    absences: Vec<Absence>,
+    new_absence: AbsenceForm,
 }
 
-enum Status<T> {
-    Loading,
-    LoadingSlowly,
-    Loaded(T),
-    Failed,
+/// The absence being declared, as typed.
+#[derive(Default)]
+struct AbsenceForm {
+    employee_id: Option<EmployeeId>,
+    from: String,
+    to: String,
+    reason: String,
 }
 
-impl<T> Default for Status<T> {
-    fn default() -> Self {
-        Self::Loading
-    }
+pub fn init(
+    config: &Config,
+    group: GroupSlug,
+    tab: SettingsTab,
+    preferences: &Preferences,
+    orders: &mut impl Orders<Msg, GMsg>,
+) -> Model {
+    orders.perform_cmd(request::load_absences(config, &group, Msg::AbsencesLoaded));
+
+    let mut model = Model {
+        employees: Status::new(storage::cached_employees(&group)),
+        group,
+        tab,
+        pick_strategy: preferences.pick_strategy,
+        ..Model::default()
+    };
+    load_list(&mut model, config, orders);
+    model
 }
 
-pub fn init(orders: &mut impl Orders<Msg, GMsg>) -> Model {
-    orders
-        .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::load_list(Msg::ListLoaded));
+pub fn group(model: &Model) -> &GroupSlug {
+    &model.group
+}
 
-    Model::default()
+/// Moves to another tab without loading the employees again.
+pub fn change_tab(model: &mut Model, tab: SettingsTab) {
+    model.tab = tab;
 }
 
+#[derive(Clone)]
 pub enum Msg {
-    ListLoaded(Result<Vec<Employee>, Vec<ErrorMessage>>),
+    ListLoaded(Loaded<Vec<Employee>>),
+    ReloadList,
     RemoveEmployee(EmployeeId),
+    EmployeeRemoved(Result<Vec<Employee>, Vec<ErrorMessage>>),
     NewEmployeeNameChanged(String),
     AddEmployee,
+    EmployeeAdded(Result<Vec<Employee>, Vec<ErrorMessage>>),
+    ImportChanged(String),
+    ImportFileChosen(Option<web_sys::File>),
+    ImportFileRead(Result<String, String>),
+    ImportEmployees,
+    EmployeesImported(Result<Vec<Employee>, Vec<ErrorMessage>>),
+    ExportCsv,
+    ExportJson,
+    RenameEmployee(EmployeeId, String),
+    SetEmployeeInactive(EmployeeId, bool),
+    SetEmployeePicked(EmployeeId, bool),
+    EmployeeUpdated(Result<Vec<Employee>, Vec<ErrorMessage>>),
+    PickStrategySelected(String),
+    PickSeedChanged(String),
+    AbsencesLoaded(Result<Vec<Absence>, Vec<ErrorMessage>>),
+    AbsenceEmployeeChanged(String),
+    AbsenceFromChanged(String),
+    AbsenceToChanged(String),
+    AbsenceReasonChanged(String),
+    DeclareAbsence,
+    AbsenceDeclared(Result<Vec<Absence>, Vec<ErrorMessage>>),
+    WithdrawAbsence(AbsenceId),
+    AbsenceWithdrawn(Result<Vec<Absence>, Vec<ErrorMessage>>),
+    StartRound,
+    RoundStarted(Result<RoundState, Vec<ErrorMessage>>),
+    EventReceived(Event),
     SlowLoadThresholdPassed,
     NoOp,
 }
 
-pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+pub fn update(msg: Msg, model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
     match msg {
-        Msg::ListLoaded(Ok(employees)) => {
-            model.employees = Status::Loaded(employees);
+        Msg::ListLoaded(result) => {
+            if let Ok(employees) = &result {
+                storage::cache_employees(&model.group, employees);
+            }
             model.new_employee_name.clear();
+            loading::finish(&mut model.employees, result, "Loading employees", orders);
         }
-        Msg::ListLoaded(Err(errors)) => {
-            model.employees = Status::Failed;
-            model.new_employee_name.clear();
-            logger::errors(errors);
+        Msg::ReloadList => {
+            load_list(model, config, orders);
+        }
+        Msg::RemoveEmployee(employee_id) if outbox::is_offline() => {
+            if let Some(employees) = model.employees.loaded() {
+                let mut employees = employees.clone();
+                if let Some(index) = employees
+                    .iter()
+                    .position(|employee| employee.uuid == employee_id)
+                {
+                    let employee = employees.remove(index);
+                    orders.send_g_msg(GMsg::Queue(Mutation::RemoveEmployee {
+                        group: model.group.clone(),
+                        employee_id,
+                        name: employee.name,
+                    }));
+                    employees_loaded(employees, model);
+                }
+            }
         }
         Msg::RemoveEmployee(employee_id) => {
-            orders
-                .skip()
-                .perform_cmd(request::remove_employee(employee_id, Msg::ListLoaded));
+            orders.skip().perform_cmd(request::remove_employee(
+                config,
+                &model.group,
+                employee_id,
+                Msg::EmployeeRemoved,
+            ));
+        }
+        Msg::EmployeeRemoved(Ok(employees)) => {
+            if let Some(previous_employees) = model.employees.loaded() {
+                for name in names_missing_from(previous_employees, &employees) {
+                    orders.send_g_msg(GMsg::Notify(Notification::success(format!(
+                        "{} removed",
+                        name
+                    ))));
+                }
+            }
+            employees_loaded(employees, model);
+        }
+        Msg::EmployeeRemoved(Err(errors)) => {
+            notification::notify_failure("Remove", &errors, orders);
         }
         Msg::NewEmployeeNameChanged(name) => {
             model.new_employee_name = name;
         }
+        Msg::AddEmployee if model.new_employee_name.trim().is_empty() => {
+            orders.send_g_msg(GMsg::Notify(Notification::warning("Name is required")));
+        }
+        Msg::AddEmployee if outbox::is_offline() => {
+            let name = std::mem::take(&mut model.new_employee_name);
+            orders.send_g_msg(GMsg::Queue(Mutation::AddEmployee {
+                group: model.group.clone(),
+                name: name.trim().to_owned(),
+            }));
+        }
         Msg::AddEmployee => {
             orders.skip().perform_cmd(request::add_employee(
-                model.new_employee_name.clone(),
-                Msg::ListLoaded,
+                config,
+                &model.group,
+                model.new_employee_name.trim().to_owned(),
+                Msg::EmployeeAdded,
             ));
         }
-        Msg::SlowLoadThresholdPassed => {
-            if let Status::Loading = model.employees {
-                model.employees = Status::LoadingSlowly
+        Msg::EmployeeAdded(Ok(employees)) => {
+            if let Some(previous_employees) = model.employees.loaded() {
+                for name in names_missing_from(&employees, previous_employees) {
+                    orders.send_g_msg(GMsg::Notify(Notification::success(format!(
+                        "{} added",
+                        name
+                    ))));
+                }
+            }
+            employees_loaded(employees, model);
+            model.new_employee_name.clear();
+        }
+        Msg::EmployeeAdded(Err(errors)) => {
+            notification::notify_failure("Add", &errors, orders);
+        }
+        Msg::ImportChanged(import) => {
+            model.import = import;
+        }
+        Msg::ImportFileChosen(Some(file)) => {
+            orders.skip().perform_cmd(async move {
+                let content = file::read_as_text(file).await;
+                Ok::<_, Msg>(Msg::ImportFileRead(
+                    content.map_err(|error| format!("{:?}", error)),
+                ))
+            });
+        }
+        Msg::ImportFileChosen(None) => (),
+        Msg::ImportFileRead(Ok(import)) => {
+            model.import = import;
+        }
+        Msg::ImportFileRead(Err(error)) => {
+            logger::error(error);
+            orders.send_g_msg(GMsg::Notify(Notification::error(
+                "The file couldn't be read",
+            )));
+        }
+        Msg::ImportEmployees => {
+            let names = match (model.employees.loaded(), roster::parse_names(&model.import)) {
+                (Some(employees), Ok(names)) => roster::preview(&names, employees)
+                    .into_iter()
+                    .filter(|entry| !entry.duplicate)
+                    .map(|entry| entry.name.to_owned())
+                    .collect::<Vec<_>>(),
+                _ => Vec::new(),
+            };
+            if names.is_empty() {
+                orders.send_g_msg(GMsg::Notify(Notification::warning("Nobody new to import")));
+            } else {
+                orders.skip().perform_cmd(request::import_employees(
+                    config,
+                    &model.group,
+                    names,
+                    Msg::EmployeesImported,
+                ));
+            }
+        }
+        Msg::EmployeesImported(Ok(employees)) => {
+            if let Some(previous_employees) = model.employees.loaded() {
+                let added = employees.len().saturating_sub(previous_employees.len());
+                orders.send_g_msg(GMsg::Notify(Notification::success(format!(
+                    "{} people imported",
+                    added
+                ))));
+            }
+            employees_loaded(employees, model);
+            model.import.clear();
+        }
+        Msg::EmployeesImported(Err(errors)) => {
+            notification::notify_failure("Import", &errors, orders);
+        }
+        Msg::ExportCsv => {
+            if let Some(employees) = model.employees.loaded() {
+                export(
+                    "employees.csv",
+                    "text/csv",
+                    &roster::to_csv(employees),
+                    orders,
+                );
+            }
+        }
+        Msg::ExportJson => {
+            if let Some(employees) = model.employees.loaded() {
+                export(
+                    "employees.json",
+                    "application/json",
+                    &roster::to_json(employees),
+                    orders,
+                );
+            }
+        }
+        Msg::RenameEmployee(employee_id, name) => {
+            update_employee(
+                UpdateEmployee {
+                    name: Some(name),
+                    ..UpdateEmployee::new(employee_id)
+                },
+                model,
+                config,
+                orders,
+            );
+        }
+        Msg::SetEmployeeInactive(employee_id, inactive) => {
+            update_employee(
+                UpdateEmployee {
+                    inactive: Some(inactive),
+                    ..UpdateEmployee::new(employee_id)
+                },
+                model,
+                config,
+                orders,
+            );
+        }
+        Msg::SetEmployeePicked(employee_id, picked) => {
+            update_employee(
+                UpdateEmployee {
+                    picked: Some(picked),
+                    ..UpdateEmployee::new(employee_id)
+                },
+                model,
+                config,
+                orders,
+            );
+        }
+        Msg::EmployeeUpdated(Ok(employees)) => {
+            if let Some(previous_employees) = model.employees.loaded() {
+                for name in names_updated(previous_employees, &employees) {
+                    orders.send_g_msg(GMsg::Notify(Notification::success(format!(
+                        "{} updated",
+                        name
+                    ))));
+                }
             }
+            employees_loaded(employees, model);
+        }
+        Msg::EmployeeUpdated(Err(errors)) => {
+            notification::notify_failure("Update", &errors, orders);
+            // the inputs show the edits which were refused, bring back the saved values
+            orders.send_msg(Msg::ReloadList);
+        }
+        Msg::PickStrategySelected(index) => {
+            let selected = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| PickStrategy::ALL.get(index).copied());
+            match (selected, model.pick_strategy) {
+                // keep the seed when the seeded strategy is selected again
+                (Some(PickStrategy::Seeded(_)), PickStrategy::Seeded(_)) | (None, _) => (),
+                (Some(pick_strategy), _) => {
+                    model.pick_strategy = pick_strategy;
+                    orders.send_g_msg(GMsg::PickStrategyChanged(pick_strategy));
+                }
+            }
+        }
+        Msg::PickSeedChanged(seed) => {
+            if let Ok(seed) = seed.parse() {
+                model.pick_strategy = PickStrategy::Seeded(seed);
+                orders.send_g_msg(GMsg::PickStrategyChanged(model.pick_strategy));
+            }
+        }
+        Msg::AbsencesLoaded(Ok(absences)) => {
+            model.absences = absences;
+        }
+        Msg::AbsencesLoaded(Err(errors)) => {
+            notification::notify_failure("Loading absences", &errors, orders);
+        }
+        Msg::AbsenceEmployeeChanged(employee_id) => {
+            model.new_absence.employee_id = employee_id.parse().ok();
+        }
+        Msg::AbsenceFromChanged(date) => {
+            model.new_absence.from = date;
+        }
+        Msg::AbsenceToChanged(date) => {
+            model.new_absence.to = date;
+        }
+        Msg::AbsenceReasonChanged(reason) => {
+            model.new_absence.reason = reason;
+        }
+        Msg::DeclareAbsence => {
+            let form = &model.new_absence;
+            match (form.employee_id, form.from.parse(), form.to.parse()) {
+                (Some(employee_id), Ok(from), Ok(to)) => {
+                    orders.skip().perform_cmd(request::declare_absence(
+                        config,
+                        &model.group,
+                        employee_id,
+                        from,
+                        to,
+                        form.reason.clone(),
+                        Msg::AbsenceDeclared,
+                    ));
+                }
+                _ => {
+                    orders.send_g_msg(GMsg::Notify(Notification::warning(
+                        "Choose who is absent and when first",
+                    )));
+                }
+            }
+        }
+        Msg::AbsenceDeclared(Ok(absences)) => {
+            model.absences = absences;
+            model.new_absence = AbsenceForm::default();
+            orders.send_g_msg(GMsg::Notify(Notification::success("Absence added")));
+        }
+        Msg::AbsenceDeclared(Err(errors)) => {
+            notification::notify_failure("Add absence", &errors, orders);
+        }
+        Msg::WithdrawAbsence(absence_id) => {
+            orders.skip().perform_cmd(request::withdraw_absence(
+                config,
+                &model.group,
+                absence_id,
+                Msg::AbsenceWithdrawn,
+            ));
+        }
+        Msg::AbsenceWithdrawn(Ok(absences)) => {
+            model.absences = absences;
+            orders.send_g_msg(GMsg::Notify(Notification::success("Absence removed")));
+        }
+        Msg::AbsenceWithdrawn(Err(errors)) => {
+            notification::notify_failure("Remove absence", &errors, orders);
+        }
+        Msg::StartRound => {
+            orders.skip().perform_cmd(request::start_round(
+                config,
+                &model.group,
+                Msg::RoundStarted,
+            ));
+        }
+        Msg::RoundStarted(Ok((employees, round))) => {
+            orders.send_g_msg(GMsg::Notify(Notification::success(format!(
+                "Round {} started",
+                round.number
+            ))));
+            employees_loaded(employees, model);
+        }
+        Msg::RoundStarted(Err(errors)) => {
+            notification::notify_failure("Start new round", &errors, orders);
+        }
+        Msg::EventReceived(
+            Event::RosterChanged(employees)
+            | Event::Picked((employees, _))
+            | Event::RoundStarted((employees, _))
+            | Event::TalksChanged((employees, _)),
+        ) => {
+            employees_loaded(employees, model);
+        }
+        Msg::EventReceived(Event::AbsencesChanged(absences)) => {
+            model.absences = absences;
+        }
+        Msg::SlowLoadThresholdPassed => {
+            model.employees.slow_load_threshold_passed();
         }
         Msg::NoOp => (),
     }
 }
 
-pub fn view<'a, Ms>(model: &Model) -> ViewPage<'a, Msg> {
+fn export(file_name: &str, mime_type: &str, content: &str, orders: &mut impl Orders<Msg, GMsg>) {
+    orders.skip();
+    if let Err(error) = file::download(file_name, mime_type, content) {
+        logger::error(error);
+        orders.send_g_msg(GMsg::Notify(Notification::error("Export failed")));
+    }
+}
+
+fn update_employee(
+    update: UpdateEmployee,
+    model: &Model,
+    config: &Config,
+    orders: &mut impl Orders<Msg, GMsg>,
+) {
+    orders.skip().perform_cmd(request::update_employee(
+        config,
+        &model.group,
+        update,
+        Msg::EmployeeUpdated,
+    ));
+}
+
+fn load_list(model: &mut Model, config: &Config, orders: &mut impl Orders<Msg, GMsg>) {
+    let (config, group) = (config.clone(), model.group.clone());
+    loading::start(
+        &mut model.employees,
+        move || request::load_list(&config, &group),
+        Msg::ListLoaded,
+        Msg::SlowLoadThresholdPassed,
+        orders,
+    );
+}
+
+/// Shows the `employees` and caches them for the next visit.
+fn employees_loaded(employees: Vec<Employee>, model: &mut Model) {
+    storage::cache_employees(&model.group, &employees);
+    model.employees = Status::Loaded(employees);
+}
+
+/// Names, as now, of the `employees` which changed since `previous_employees`.
+fn names_updated<'a>(previous_employees: &[Employee], employees: &'a [Employee]) -> Vec<&'a str> {
+    employees
+        .iter()
+        .filter(|employee| {
+            previous_employees.iter().any(|previous| {
+                previous.uuid == employee.uuid
+                    && (previous.name != employee.name
+                        || previous.inactive != employee.inactive
+                        || previous.picked != employee.picked)
+            })
+        })
+        .map(|employee| employee.name.as_str())
+        .collect()
+}
+
+/// Names of the `employees` which aren't in `others`.
+fn names_missing_from<'a>(employees: &'a [Employee], others: &[Employee]) -> Vec<&'a str> {
+    employees
+        .iter()
+        .filter(|employee| others.iter().all(|other| other.uuid != employee.uuid))
+        .map(|employee| employee.name.as_str())
+        .collect()
+}
+
+pub fn view<'a>(model: &Model) -> ViewPage<'a, Msg> {
     ViewPage::new("Settings", view_content(model))
 }
 
 fn view_content(model: &Model) -> Node<Msg> {
-    match &model.employees {
-        Status::Loading => empty![],
-        Status::LoadingSlowly => loading::view_icon(),
-        Status::Failed => loading::view_error("employees"),
-        Status::Loaded(employees) => div![
-            class!["container"],
-            table![
-                class!["table"],
-                thead![tr![td![
-                    attrs! {At::ColSpan => 2},
+    div![
+        view_tabs(model),
+        match model.tab {
+            SettingsTab::Employees => view_employees(model),
+            SettingsTab::Absences => view_absences(model),
+            SettingsTab::Import => view_import_export(model),
+            SettingsTab::Picking => div![view_pick_strategy(model.pick_strategy), view_round()],
+        },
+    ]
+}
+
+fn view_tabs(model: &Model) -> Node<Msg> {
+    div![
+        class!["tabs is-centered"],
+        ul![SettingsTab::ALL.iter().map(|tab| {
+            let route = Route::Settings {
+                group: model.group.clone(),
+                tab: *tab,
+            };
+            li![
+                class!["is-active" => *tab == model.tab],
+                a![attrs! {At::Href => route.to_string()}, tab.label()],
+            ]
+        })],
+    ]
+}
+
+fn view_employees(model: &Model) -> Node<Msg> {
+    loading::view(
+        &model.employees,
+        "employees",
+        Msg::ReloadList,
+        |employees| {
+            div![
+                class!["container"],
+                table![
+                    class!["table"],
+                    thead![tr![td![
+                        attrs! {At::ColSpan => 5},
+                        input![
+                            attrs! {At::Value => model.new_employee_name},
+                            keyboard_ev(Ev::KeyDown, |keyboard_event| {
+                                if keyboard_event.key_code() == ENTER_KEY {
+                                    Msg::AddEmployee
+                                } else {
+                                    Msg::NoOp
+                                }
+                            }),
+                            input_ev(Ev::Input, Msg::NewEmployeeNameChanged),
+                        ],
+                    ],],],
+                    tbody![employees
+                        .iter()
+                        .map(|employee| view_employee(&model.group, employee))],
+                ],
+            ]
+        },
+    )
+}
+
+fn view_employee(group: &GroupSlug, employee: &Employee) -> Node<Msg> {
+    let employee_id = employee.uuid;
+    let profile = Route::Employee(group.clone(), employee_id);
+    tr![
+        td![input![
+            class!["input is-small"],
+            attrs! {At::Value => employee.name, At::Disabled => employee.inactive.as_at_value()},
+            input_ev(Ev::Change, move |name| Msg::RenameEmployee(
+                employee_id,
+                name
+            )),
+        ]],
+        td![a![attrs! {At::Href => profile.to_string()}, "Profile"]],
+        td![label![
+            class!["checkbox"],
+            input![
+                attrs! {
+                    At::Type => "checkbox",
+                    At::Checked => employee.picked.as_at_value(),
+                    At::Disabled => employee.inactive.as_at_value(),
+                },
+                simple_ev(
+                    Ev::Change,
+                    Msg::SetEmployeePicked(employee_id, !employee.picked)
+                ),
+            ],
+            " Spoke this round",
+        ]],
+        td![button![
+            class!["button is-small"],
+            simple_ev(
+                Ev::Click,
+                Msg::SetEmployeeInactive(employee_id, !employee.inactive)
+            ),
+            if employee.inactive {
+                "Reactivate"
+            } else {
+                "Deactivate"
+            },
+        ]],
+        td![if employee.inactive {
+            div![
+                class!["delete is-small"],
+                attrs! {At::Title => "Remove for good"},
+                simple_ev(Ev::Click, Msg::RemoveEmployee(employee_id)),
+            ]
+        } else {
+            empty![]
+        }],
+    ]
+}
+
+fn view_import_export(model: &Model) -> Node<Msg> {
+    let employees = match model.employees.loaded() {
+        Some(employees) => employees,
+        None => return empty![],
+    };
+
+    div![
+        class!["container"],
+        label![class!["label"], "Import"],
+        div![
+            class!["field"],
+            div![
+                class!["file is-small"],
+                label![
+                    class!["file-label"],
                     input![
-                        attrs! {At::Value => model.new_employee_name},
-                        keyboard_ev(Ev::KeyDown, |keyboard_event| {
-                            if keyboard_event.key_code() == ENTER_KEY {
-                                Msg::AddEmployee
-                            } else {
-                                Msg::NoOp
-                            }
-                        }),
-                        input_ev(Ev::Input, Msg::NewEmployeeNameChanged),
+                        class!["file-input"],
+                        attrs! {At::Type => "file", At::Accept => ".csv,.json,text/csv,application/json"},
+                        ev(Ev::Change, |event| Msg::ImportFileChosen(chosen_file(
+                            &event
+                        ))),
+                    ],
+                    span![
+                        class!["file-cta"],
+                        span![class!["file-label"], "Choose a CSV or JSON file"]
                     ],
-                ],],],
-                tbody![employees.iter().map(|employee| tr![
-                    td![employee.name],
+                ],
+            ],
+        ],
+        div![
+            class!["field"],
+            textarea![
+                class!["textarea is-small"],
+                attrs! {
+                    At::Placeholder => "Or paste the names, one per line",
+                    At::Rows => 4,
+                    At::Value => model.import,
+                },
+                input_ev(Ev::Input, Msg::ImportChanged),
+            ],
+        ],
+        view_import_preview(&model.import, employees),
+        div![
+            class!["buttons"],
+            label![class!["label"], "Export"],
+            button![
+                class!["button is-small"],
+                simple_ev(Ev::Click, Msg::ExportCsv),
+                "CSV"
+            ],
+            button![
+                class!["button is-small"],
+                simple_ev(Ev::Click, Msg::ExportJson),
+                "JSON"
+            ],
+        ],
+    ]
+}
+
+fn view_import_preview(import: &str, employees: &[Employee]) -> Node<Msg> {
+    if import.trim().is_empty() {
+        return empty![];
+    }
+    let names = match roster::parse_names(import) {
+        Ok(names) => names,
+        Err(error) => return p![class!["help is-danger"], error],
+    };
+    let entries = roster::preview(&names, employees);
+    let new_entries = entries.iter().filter(|entry| !entry.duplicate).count();
+
+    div![
+        class!["field"],
+        div![
+            class!["tags"],
+            entries.iter().map(|entry| if entry.duplicate {
+                span![
+                    class!["tag is-warning"],
+                    attrs! {At::Title => "Already in the roster"},
+                    entry.name
+                ]
+            } else {
+                span![class!["tag is-success"], entry.name]
+            }),
+        ],
+        button![
+            class!["button is-primary is-small"],
+            attrs! {At::Disabled => (new_entries == 0).as_at_value()},
+            simple_ev(Ev::Click, Msg::ImportEmployees),
+            format!("Add {} people", new_entries),
+        ],
+    ]
+}
+
+/// The file chosen in the file input which is the target of the `event`.
+fn chosen_file(event: &web_sys::Event) -> Option<web_sys::File> {
+    event
+        .target()?
+        .dyn_into::<web_sys::HtmlInputElement>()
+        .ok()?
+        .files()?
+        .get(0)
+}
+
+fn view_absences(model: &Model) -> Node<Msg> {
+    let employees = match model.employees.loaded() {
+        Some(employees) => employees,
+        None => return empty![],
+    };
+    let form = &model.new_absence;
+
+    div![
+        class!["container"],
+        label![class!["label"], "Absences"],
+        table![
+            class!["table"],
+            thead![tr![
+                td![div![
+                    class!["select is-small"],
+                    select![
+                        option![attrs! {At::Value => ""}, "Who?"],
+                        employees.iter().map(|employee| option![
+                            attrs! {
+                                At::Value => employee.uuid,
+                                At::Selected => (form.employee_id == Some(employee.uuid)).as_at_value(),
+                            },
+                            employee.name,
+                        ]),
+                        input_ev(Ev::Change, Msg::AbsenceEmployeeChanged),
+                    ],
+                ]],
+                td![input![
+                    class!["input is-small"],
+                    attrs! {At::Type => "date", At::Title => "From", At::Value => form.from},
+                    input_ev(Ev::Input, Msg::AbsenceFromChanged),
+                ]],
+                td![input![
+                    class!["input is-small"],
+                    attrs! {At::Type => "date", At::Title => "To", At::Value => form.to},
+                    input_ev(Ev::Input, Msg::AbsenceToChanged),
+                ]],
+                td![input![
+                    class!["input is-small"],
+                    attrs! {At::Placeholder => "Reason", At::Value => form.reason},
+                    input_ev(Ev::Input, Msg::AbsenceReasonChanged),
+                ]],
+                td![button![
+                    class!["button is-small is-primary"],
+                    simple_ev(Ev::Click, Msg::DeclareAbsence),
+                    "Add",
+                ]],
+            ]],
+            tbody![model.absences.iter().map(|absence| {
+                let name = employees
+                    .iter()
+                    .find(|employee| employee.uuid == absence.employee_id)
+                    .map_or("Former colleague", |employee| employee.name.as_str());
+                let absence_id = absence.uuid;
+                tr![
+                    td![name],
+                    td![absence.from.to_string()],
+                    td![absence.to.to_string()],
+                    td![absence.reason],
                     td![div![
                         class!["delete is-small"],
-                        ev(
-                            Ev::Click,
-                            enc!((employee) move |_| Msg::RemoveEmployee(employee.uuid))
-                        )
+                        simple_ev(Ev::Click, Msg::WithdrawAbsence(absence_id)),
                     ]],
-                ])],
+                ]
+            })],
+        ],
+    ]
+}
+
+fn view_pick_strategy(pick_strategy: PickStrategy) -> Node<Msg> {
+    let selected_index = PickStrategy::ALL
+        .iter()
+        .position(|strategy| {
+            std::mem::discriminant(strategy) == std::mem::discriminant(&pick_strategy)
+        })
+        .unwrap_or_default();
+
+    div![
+        class!["container"],
+        div![
+            class!["field"],
+            label![class!["label"], "Pick strategy"],
+            div![
+                class!["select"],
+                select![
+                    PickStrategy::ALL
+                        .iter()
+                        .enumerate()
+                        .map(|(index, strategy)| option![
+                            attrs! {
+                                At::Value => index,
+                                At::Selected => (index == selected_index).as_at_value(),
+                            },
+                            strategy.label(),
+                        ]),
+                    input_ev(Ev::Change, Msg::PickStrategySelected),
+                ],
             ],
         ],
-    }
+        if let PickStrategy::Seeded(seed) = pick_strategy {
+            div![
+                class!["field"],
+                label![class!["label"], "Seed"],
+                input![
+                    class!["input"],
+                    attrs! {At::Type => "number", At::Min => 0, At::Value => seed},
+                    input_ev(Ev::Input, Msg::PickSeedChanged),
+                ],
+            ]
+        } else {
+            empty![]
+        },
+    ]
+}
+
+fn view_round() -> Node<Msg> {
+    div![
+        class!["container"],
+        div![
+            class!["field"],
+            label![class!["label"], "Round"],
+            p![
+                class!["help"],
+                "Everybody can be picked again once a new round starts."
+            ],
+        ],
+        button![
+            class!["button is-warning"],
+            simple_ev(Ev::Click, Msg::StartRound),
+            "Start new round",
+        ],
+    ]
 }
@@ -0,0 +1,14 @@
+use crate::theme::Theme;
+use caliaconf_api::entity::GroupSlug;
+use caliaconf_api::pick::PickStrategy;
+
+// ------ Preferences ------
+
+/// Choices of the user, shared by all the pages.
+#[derive(Clone, Debug, Default)]
+pub struct Preferences {
+    pub pick_strategy: PickStrategy,
+    pub theme: Theme,
+    /// The group visited last, opened when the app starts on the list of the groups.
+    pub group: Option<GroupSlug>,
+}
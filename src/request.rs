@@ -1,38 +1,114 @@
-use crate::{entity::ErrorMessage, logger};
-use seed::fetch;
+use crate::loading::Loaded;
+use crate::{config::Config, logger};
+use caliaconf_api::endpoint::{self, Endpoint, ErrorBody};
+use caliaconf_api::entity::{ErrorKind, ErrorMessage};
+use seed::fetch::{self, ResponseDataResult};
+use std::convert::identity;
 use std::fmt::Debug;
+use std::future::Future;
 
+#[allow(clippy::module_inception)]
 pub mod request;
 
-static BASE_API_URL: &str = "https://franz_strudel-caliaconf.builtwithdark.com";
-const TIMEOUT: u32 = 5000;
+/// A request to the API, with the token of the logged in user if any.
+pub fn new(path: &str, config: &Config) -> fetch::Request {
+    let request =
+        fetch::Request::new(format!("{}/{}", config.api_url, path)).timeout(config.timeout);
+    match &config.token {
+        Some(token) => request.header("Authorization", &format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+/// Calls the endpoint `E` of the `group` (`None` for the endpoints which aren't scoped to a group)
+/// with `payload` as JSON body (except for `GET` endpoints).
+pub fn send<E, Ms>(
+    config: &Config,
+    group: Option<&str>,
+    payload: &E::Request,
+    f: fn(Result<E::Response, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>>
+where
+    E: Endpoint<Error = ErrorBody>,
+    E::Response: 'static,
+    Ms: 'static,
+{
+    let path = group.map_or_else(
+        || E::PATH.to_owned(),
+        |group| endpoint::group_path(group, E::PATH),
+    );
+    let request = new(&path, config);
+    let request = match E::METHOD {
+        endpoint::Method::Get => request.method(fetch::Method::Get),
+        endpoint::Method::Post => request.method(fetch::Method::Post).send_json(payload),
+    };
+    request.fetch_json_data(move |data_result: ResponseDataResult<E::Response>| {
+        f(data_result.map_err(fail_reason_into_errors))
+    })
+}
 
-pub fn new(path: &str) -> fetch::Request {
-    fetch::Request::new(format!("{}/{}", BASE_API_URL, path)).timeout(TIMEOUT)
+/// Loads the response of the `GET` endpoint `E` of the `group`, failed or not.
+pub fn load<E>(config: &Config, group: Option<&str>) -> impl Future<Output = Loaded<E::Response>>
+where
+    E: Endpoint<Request = (), Error = ErrorBody>,
+    E::Response: 'static,
+{
+    let response = send::<E, _>(config, group, &(), identity);
+    async move { response.await.unwrap_or_else(identity) }
 }
 
 pub fn fail_reason_into_errors<T: Debug>(fail_reason: fetch::FailReason<T>) -> Vec<ErrorMessage> {
     match fail_reason {
-        fetch::FailReason::RequestError(request_error, _) => {
-            logger::error(request_error);
-            vec!["Request error".into()]
+        fetch::FailReason::RequestError(fetch::RequestError::DomException(exception), _) => {
+            logger::error(&exception);
+            // requests are only aborted by their timeout
+            if exception.name() == "AbortError" {
+                vec![ErrorMessage::new(
+                    ErrorKind::Timeout,
+                    "The server took too long to answer",
+                )]
+            } else {
+                vec![ErrorMessage::new(
+                    ErrorKind::Network,
+                    "The server can't be reached",
+                )]
+            }
         }
-        fetch::FailReason::DataError(data_error, _) => {
+        fetch::FailReason::DataError(data_error, fetch_object) => {
             logger::error(data_error);
-            vec!["Data error".into()]
+            let error = ErrorMessage::new(ErrorKind::Decode, "Unexpected response from the server");
+            match fetch_object.result {
+                Ok(response) => vec![error.with_status(response.status.code)],
+                Err(_) => vec![error],
+            }
         }
-        fetch::FailReason::Status(_, fetch_object) => {
+        fetch::FailReason::Status(status, fetch_object) => {
+            let kind = match status.category {
+                fetch::StatusCategory::ClientError => ErrorKind::Validation,
+                _ => ErrorKind::Server,
+            };
             // response isn't ok, but maybe contains error messages - try to decode them:
-            match fetch_object.result.unwrap().data {
-                Err(fetch::DataError::SerdeError(serde_error, json)) => {
-                    logger::error(serde_error);
-                    vec!["Data error".into()]
+            let errors = match fetch_object.result.map(|response| response.data) {
+                Ok(Err(fetch::DataError::SerdeError(_, json))) => {
+                    serde_json::from_str::<ErrorBody>(&json)
+                        .map(|error_body| error_body.errors)
+                        .unwrap_or_default()
                 }
-                data => {
-                    logger::error(data);
-                    vec!["Data error".into()]
-                }
-            }
+                _ => Vec::new(),
+            };
+            let code = status.code;
+            let errors = if !errors.is_empty() {
+                errors
+            } else if !status.text.is_empty() {
+                vec![status.text]
+            } else {
+                vec![kind.to_string()]
+            };
+            logger::errors(&errors);
+            errors
+                .into_iter()
+                .map(|error| ErrorMessage::new(kind, error).with_status(code))
+                .collect()
         }
     }
 }
@@ -1,63 +1,184 @@
-use crate::entity::{Employee, EmployeeId};
-use crate::{entity::ErrorMessage, request};
-use seed::fetch::ResponseDataResult;
-use seed::Method;
-use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::loading::Loaded;
+use crate::request;
+use caliaconf_api::endpoint::{
+    self, AcceptPick, AddEmployee, AddGroup, CancelTalk, ConfirmTalk, Credentials, DeclareAbsence,
+    DrawEmployee, ImportEmployees, Picked, RemoveEmployee, RescheduleTalk, RoundState, Session,
+    TalkHistory, UpdateEmployee, WithdrawAbsence,
+};
+use caliaconf_api::entity::{
+    Absence, AbsenceId, Employee, EmployeeId, ErrorMessage, Group, TalkId,
+};
+use caliaconf_api::pick::PickStrategy;
+use chrono::NaiveDate;
+use std::future::Future;
 
-pub async fn load_list<Ms: 'static>(
-    f: fn(Result<Vec<Employee>, Vec<ErrorMessage>>) -> Ms,
-) -> Result<Ms, Ms> {
-    request::new("list")
-        .fetch_json_data(move |data_result: ResponseDataResult<Vec<Employee>>| {
-            f(data_result.map_err(request::fail_reason_into_errors))
-        })
-        .await
+pub fn login<Ms: 'static>(
+    config: &Config,
+    credentials: Credentials,
+    f: fn(Result<Session, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Login, _>(config, None, &credentials, f)
+}
+
+pub fn load_groups(config: &Config) -> impl Future<Output = Loaded<Vec<Group>>> {
+    request::load::<endpoint::Groups>(config, None)
+}
+
+pub fn create_group<Ms: 'static>(
+    config: &Config,
+    name: String,
+    f: fn(Result<Vec<Group>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::CreateGroup, _>(config, None, &AddGroup { name }, f)
+}
+
+pub fn load_list(config: &Config, group: &str) -> impl Future<Output = Loaded<Vec<Employee>>> {
+    request::load::<endpoint::List>(config, Some(group))
 }
 
-pub async fn remove_employee<Ms: 'static>(
+pub fn remove_employee<Ms: 'static>(
+    config: &Config,
+    group: &str,
     employee_id: EmployeeId,
     f: fn(Result<Vec<Employee>, Vec<ErrorMessage>>) -> Ms,
-) -> Result<Ms, Ms> {
-    #[derive(Serialize)]
-    struct Payload {
-        employee_id: EmployeeId,
-    };
-    request::new("remove")
-        .method(Method::Post)
-        .send_json(&Payload { employee_id })
-        .fetch_json_data(move |data_result: ResponseDataResult<Vec<Employee>>| {
-            f(data_result.map_err(request::fail_reason_into_errors))
-        })
-        .await
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Remove, _>(config, Some(group), &RemoveEmployee { employee_id }, f)
 }
 
-pub async fn add_employee<Ms: 'static>(
+pub fn add_employee<Ms: 'static>(
+    config: &Config,
+    group: &str,
     name: String,
     f: fn(Result<Vec<Employee>, Vec<ErrorMessage>>) -> Ms,
-) -> Result<Ms, Ms> {
-    #[derive(Serialize)]
-    struct Payload {
-        name: String,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Add, _>(config, Some(group), &AddEmployee { name }, f)
+}
+
+pub fn import_employees<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    names: Vec<String>,
+    f: fn(Result<Vec<Employee>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Import, _>(config, Some(group), &ImportEmployees { names }, f)
+}
+
+pub fn update_employee<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    update: UpdateEmployee,
+    f: fn(Result<Vec<Employee>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Update, _>(config, Some(group), &update, f)
+}
+
+pub fn draw_employee<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    strategy: PickStrategy,
+    excluded: Vec<EmployeeId>,
+    f: fn(Result<Option<Employee>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Draw, _>(config, Some(group), &DrawEmployee { strategy, excluded }, f)
+}
+
+pub fn accept_pick<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    employee_id: EmployeeId,
+    f: fn(Result<Picked, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Accept, _>(config, Some(group), &AcceptPick { employee_id }, f)
+}
+
+pub fn load_talks(config: &Config, group: &str) -> impl Future<Output = Loaded<TalkHistory>> {
+    request::load::<endpoint::Talks>(config, Some(group))
+}
+
+pub fn confirm_talk<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    talk_id: TalkId,
+    title: String,
+    slides: Option<String>,
+    f: fn(Result<TalkHistory, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    let payload = ConfirmTalk {
+        talk_id,
+        title,
+        slides,
+    };
+    request::send::<endpoint::Confirm, _>(config, Some(group), &payload, f)
+}
+
+pub fn reschedule_talk<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    talk_id: TalkId,
+    date: NaiveDate,
+    f: fn(Result<TalkHistory, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Reschedule, _>(
+        config,
+        Some(group),
+        &RescheduleTalk { talk_id, date },
+        f,
+    )
+}
+
+pub fn cancel_talk<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    talk_id: TalkId,
+    f: fn(Result<TalkHistory, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Cancel, _>(config, Some(group), &CancelTalk { talk_id }, f)
+}
+
+pub fn load_round(config: &Config, group: &str) -> impl Future<Output = Loaded<RoundState>> {
+    request::load::<endpoint::CurrentRound>(config, Some(group))
+}
+
+pub fn start_round<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    f: fn(Result<RoundState, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::NewRound, _>(config, Some(group), &(), f)
+}
+
+pub fn load_absences<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    f: fn(Result<Vec<Absence>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Absences, _>(config, Some(group), &(), f)
+}
+
+pub fn declare_absence<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    employee_id: EmployeeId,
+    from: NaiveDate,
+    to: NaiveDate,
+    reason: String,
+    f: fn(Result<Vec<Absence>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    let payload = DeclareAbsence {
+        employee_id,
+        from,
+        to,
+        reason,
     };
-    request::new("add")
-        .method(Method::Post)
-        .send_json(&Payload { name })
-        .fetch_json_data(move |data_result: ResponseDataResult<Vec<Employee>>| {
-            f(data_result.map_err(request::fail_reason_into_errors))
-        })
-        .await
-}
-
-pub async fn pick_employee<Ms: 'static>(
-    f: fn(Result<(Vec<Employee>, Option<Employee>), Vec<ErrorMessage>>) -> Ms,
-) -> Result<Ms, Ms> {
-    #[derive(Serialize)]
-    request::new("pick")
-        .method(Method::Get)
-        .fetch_json_data(
-            move |data_result: ResponseDataResult<(Vec<Employee>, Option<Employee>)>| {
-                f(data_result.map_err(request::fail_reason_into_errors))
-            },
-        )
-        .await
+    request::send::<endpoint::Declare, _>(config, Some(group), &payload, f)
+}
+
+pub fn withdraw_absence<Ms: 'static>(
+    config: &Config,
+    group: &str,
+    absence_id: AbsenceId,
+    f: fn(Result<Vec<Absence>, Vec<ErrorMessage>>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::send::<endpoint::Withdraw, _>(config, Some(group), &WithdrawAbsence { absence_id }, f)
 }
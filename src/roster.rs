@@ -0,0 +1,247 @@
+use caliaconf_api::entity::Employee;
+use serde_json::Value;
+
+/// A name to import, flagged when the person is already in the roster
+/// or appears earlier in the import.
+pub struct Entry<'a> {
+    pub name: &'a str,
+    pub duplicate: bool,
+}
+
+/// Names of a roster pasted or uploaded by the user:
+/// - JSON: a list of names, or of objects with a `name` (like the JSON export),
+/// - CSV: one person per line, in the `name` column when there is a header (like the CSV export),
+///   in the first column otherwise. The first line is only taken for a header when it has other
+///   columns of the export too.
+pub fn parse_names(content: &str) -> Result<Vec<String>, String> {
+    let content = content.trim();
+    if content.starts_with('[') {
+        parse_json(content)
+    } else {
+        Ok(parse_csv(content))
+    }
+}
+
+pub fn preview<'a>(names: &'a [String], employees: &[Employee]) -> Vec<Entry<'a>> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| Entry {
+            name,
+            duplicate: employees
+                .iter()
+                .map(|employee| employee.name.as_str())
+                .chain(names[..index].iter().map(String::as_str))
+                .any(|other| same_name(other, name)),
+        })
+        .collect()
+}
+
+pub fn to_json(employees: &[Employee]) -> String {
+    serde_json::to_string_pretty(employees).unwrap_or_default()
+}
+
+pub fn to_csv(employees: &[Employee]) -> String {
+    let mut csv = String::from("uuid,name,picked,inactive\n");
+    for employee in employees {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            employee.uuid,
+            csv_field(&employee.name),
+            employee.picked,
+            employee.inactive
+        ));
+    }
+    csv
+}
+
+// ====== PRIVATE ======
+
+/// Columns of the CSV export besides the `name`.
+const HEADER_COLUMNS: [&str; 3] = ["uuid", "picked", "inactive"];
+
+fn parse_json(content: &str) -> Result<Vec<String>, String> {
+    let entries = match serde_json::from_str(content) {
+        Ok(Value::Array(entries)) => entries,
+        Ok(_) => return Err("Expected a list of names".into()),
+        Err(error) => return Err(format!("Invalid JSON: {}", error)),
+    };
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            Value::String(name) => Ok(name),
+            Value::Object(mut fields) => match fields.remove("name") {
+                Some(Value::String(name)) => Ok(name),
+                _ => Err("Each person needs a name".into()),
+            },
+            _ => Err("Expected a list of names".into()),
+        })
+        .map(|name| name.map(|name| name.trim().to_owned()))
+        .filter(|name| name.as_ref().map_or(true, |name| !name.is_empty()))
+        .collect()
+}
+
+fn parse_csv(content: &str) -> Vec<String> {
+    let mut rows = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(split_csv_line)
+        .peekable();
+
+    // A person may be called "Name": a header also has another column of the export.
+    let name_column = rows
+        .peek()
+        .filter(|header| {
+            header
+                .iter()
+                .any(|field| HEADER_COLUMNS.iter().any(|column| same_name(field, column)))
+        })
+        .and_then(|header| header.iter().position(|field| same_name(field, "name")));
+    if name_column.is_some() {
+        rows.next();
+    }
+
+    rows.filter_map(|mut row| {
+        let name = row.swap_remove(name_column.unwrap_or_default().min(row.len() - 1));
+        Some(name.trim().to_owned()).filter(|name| !name.is_empty())
+    })
+    .collect()
+}
+
+/// Fields of a CSV line separated by commas or semicolons, which may be quoted.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(character) = chars.next() {
+        let field = fields.last_mut().expect("there is always a field");
+        match character {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' | ';' if !quoted => fields.push(String::new()),
+            character => field.push(character),
+        }
+    }
+    fields
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', ';', '"', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn same_name(name: &str, other: &str) -> bool {
+    name.trim().to_lowercase() == other.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn employee(name: &str) -> Employee {
+        Employee {
+            uuid: Uuid::new_v4(),
+            name: name.to_owned(),
+            picked: false,
+            inactive: false,
+            joined_at: None,
+            last_picked_at: None,
+        }
+    }
+
+    #[test]
+    fn quoted_fields_keep_separators_and_quotes() {
+        assert_eq!(
+            split_csv_line(r#"1,"Doe, ""Jo"" ; Jr",true"#),
+            vec!["1", r#"Doe, "Jo" ; Jr"#, "true"]
+        );
+    }
+
+    #[test]
+    fn semicolons_separate_fields_too() {
+        assert_eq!(
+            split_csv_line("Ada;Lovelace,x"),
+            vec!["Ada", "Lovelace", "x"]
+        );
+    }
+
+    #[test]
+    fn names_are_read_from_the_first_column_without_header() {
+        assert_eq!(
+            parse_names("Ada;dev\n\n  Brian , ops\n;\n"),
+            Ok(vec!["Ada".to_owned(), "Brian".to_owned()])
+        );
+    }
+
+    #[test]
+    fn names_are_read_from_the_name_column_of_the_export() {
+        let employees = vec![employee("Ada"), employee("Doe, Jo")];
+        assert_eq!(
+            parse_names(&to_csv(&employees)),
+            Ok(vec!["Ada".to_owned(), "Doe, Jo".to_owned()])
+        );
+        assert_eq!(
+            parse_names("Picked;NAME\ntrue;Ada"),
+            Ok(vec!["Ada".to_owned()])
+        );
+    }
+
+    #[test]
+    fn a_person_called_name_is_not_a_header() {
+        assert_eq!(
+            parse_names("Name\nAda"),
+            Ok(vec!["Name".to_owned(), "Ada".to_owned()])
+        );
+        assert_eq!(
+            parse_names("Name,Smith\nAda,Lovelace"),
+            Ok(vec!["Name".to_owned(), "Ada".to_owned()])
+        );
+    }
+
+    #[test]
+    fn names_are_read_from_json() {
+        assert_eq!(
+            parse_names(r#"["Ada", " ", {"name": " Brian "}]"#),
+            Ok(vec!["Ada".to_owned(), "Brian".to_owned()])
+        );
+        let employees = vec![employee("Ada")];
+        assert_eq!(
+            parse_names(&to_json(&employees)),
+            Ok(vec!["Ada".to_owned()])
+        );
+        assert!(parse_names(r#"[{"uuid": 1}]"#).is_err());
+        assert!(parse_names("[1, 2]").is_err());
+        assert!(parse_names("[").is_err());
+    }
+
+    #[test]
+    fn preview_flags_duplicates_in_the_roster_and_the_import() {
+        let employees = vec![employee("Ada")];
+        let names = vec![
+            "ada ".to_owned(),
+            "Brian".to_owned(),
+            "brian".to_owned(),
+            "Chloe".to_owned(),
+        ];
+        let duplicates = preview(&names, &employees)
+            .iter()
+            .map(|entry| (entry.name, entry.duplicate))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            duplicates,
+            vec![
+                ("ada ", true),
+                ("Brian", false),
+                ("brian", true),
+                ("Chloe", false)
+            ]
+        );
+    }
+}
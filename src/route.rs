@@ -3,6 +3,7 @@ use std::{convert::TryFrom, fmt};
 use seed::prelude::*;
 
 use crate::GMsg;
+use caliaconf_api::entity::{user, EmployeeId, GroupSlug, Role, User};
 
 pub fn go_to<Ms: 'static>(route: Route, orders: &mut impl Orders<Ms, GMsg>) {
     seed::push_route(route.clone());
@@ -11,32 +12,136 @@ pub fn go_to<Ms: 'static>(route: Route, orders: &mut impl Orders<Ms, GMsg>) {
 
 // ------ Route ------
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Route {
-    Home,
+    /// The list of the groups.
     Root,
-    Settings,
+    Login,
+    Home(GroupSlug),
+    /// The timer of the talk being given.
+    Session(GroupSlug),
+    Employee(GroupSlug, EmployeeId),
+    History {
+        group: GroupSlug,
+        /// Starts at 1.
+        page: u32,
+        person: Option<EmployeeId>,
+    },
+    Settings {
+        group: GroupSlug,
+        tab: SettingsTab,
+    },
 }
 
 impl Route {
-    pub fn path(&self) -> Vec<&str> {
+    pub fn history(group: GroupSlug) -> Self {
+        Route::History {
+            group,
+            page: 1,
+            person: None,
+        }
+    }
+
+    pub fn settings(group: GroupSlug) -> Self {
+        Route::Settings {
+            group,
+            tab: SettingsTab::default(),
+        }
+    }
+
+    pub fn path(&self) -> Vec<String> {
+        use Route::*;
+        let mut path = match (self, self.group()) {
+            (Login, _) => return vec!["login".to_owned()],
+            (_, Some(group)) => vec!["g".to_owned(), group.clone()],
+            (_, None) => return Vec::new(),
+        };
+        match self {
+            Root | Login | Home(_) => (),
+            Session(_) => path.push("session".to_owned()),
+            Employee(_, employee_id) => {
+                path.extend(vec!["employees".to_owned(), employee_id.to_string()]);
+            }
+            History { .. } => path.push("history".to_owned()),
+            Settings { tab, .. } => {
+                path.push("settings".to_owned());
+                if *tab != SettingsTab::default() {
+                    path.push(tab.as_str().to_owned());
+                }
+            }
+        }
+        path
+    }
+
+    /// Query parameters, the ones with their default value are left out.
+    pub fn query(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Route::History { page, person, .. } => {
+                let page = Some(page)
+                    .filter(|page| **page != 1)
+                    .map(|page| ("page", page.to_string()));
+                let person = person.map(|person| ("person", person.to_string()));
+                page.into_iter().chain(person).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The group the route belongs to.
+    pub fn group(&self) -> Option<&GroupSlug> {
         use Route::*;
         match self {
-            Home | Root => vec![],
-            Settings => vec!["settings"],
+            Root | Login => None,
+            Home(group) | Session(group) | Employee(group, _) => Some(group),
+            History { group, .. } | Settings { group, .. } => Some(group),
+        }
+    }
+
+    /// Least role allowed to open the route, `None` when it's open to anonymous users.
+    pub fn role(&self) -> Option<Role> {
+        match self {
+            Route::Login => None,
+            Route::Settings { .. } => Some(Role::Admin),
+            _ => Some(Role::Viewer),
+        }
+    }
+
+    /// Whether the `user`, `None` when nobody is logged in, may open the route.
+    pub fn is_allowed(&self, user: Option<&User>) -> bool {
+        user::is_allowed(user, self.role())
+    }
+
+    fn search(&self) -> Option<String> {
+        let query = self.query();
+        if query.is_empty() {
+            None
+        } else {
+            let parameters = query
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>();
+            Some(parameters.join("&"))
         }
     }
 }
 
 impl fmt::Display for Route {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "/{}", self.path().join("/"))
+        write!(f, "/{}", self.path().join("/"))?;
+        match self.search() {
+            Some(search) => write!(f, "?{}", search),
+            None => Ok(()),
+        }
     }
 }
 
 impl From<Route> for seed::Url {
     fn from(route: Route) -> Self {
-        route.path().into()
+        let url = seed::Url::new(route.path());
+        match route.search() {
+            Some(search) => url.search(&search),
+            None => url,
+        }
     }
 }
 
@@ -44,13 +149,211 @@ impl TryFrom<seed::Url> for Route {
     type Error = ();
 
     fn try_from(url: seed::Url) -> Result<Self, Self::Error> {
-        let mut path = url.path.into_iter();
+        Self::parse(&url).ok_or(())
+    }
+}
+
+impl Route {
+    fn parse(url: &seed::Url) -> Option<Self> {
+        let path = url.path.iter().map(String::as_str).collect::<Vec<_>>();
+        let query = Query::parse(url.search.as_deref().unwrap_or_default());
+
+        Some(match path.as_slice() {
+            [] | [""] => Route::Root,
+            ["login"] => Route::Login,
+            ["g", group] => Route::Home((*group).to_owned()),
+            ["g", group, "session"] => Route::Session((*group).to_owned()),
+            ["g", group, "employees", employee_id] => {
+                Route::Employee((*group).to_owned(), employee_id.parse().ok()?)
+            }
+            ["g", group, "history"] => Route::History {
+                group: (*group).to_owned(),
+                page: match query.get("page") {
+                    Some(page) => page.parse().ok().filter(|page| *page > 0)?,
+                    None => 1,
+                },
+                person: match query.get("person") {
+                    Some(person) => Some(person.parse().ok()?),
+                    None => None,
+                },
+            },
+            ["g", group, "settings"] => Route::settings((*group).to_owned()),
+            ["g", group, "settings", tab] => Route::Settings {
+                group: (*group).to_owned(),
+                tab: SettingsTab::from_str(tab)?,
+            },
+            _ => return None,
+        })
+    }
+}
+
+// ------ SettingsTab ------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SettingsTab {
+    #[default]
+    Employees,
+    Absences,
+    Import,
+    Picking,
+}
+
+impl SettingsTab {
+    pub const ALL: [SettingsTab; 4] = [
+        SettingsTab::Employees,
+        SettingsTab::Absences,
+        SettingsTab::Import,
+        SettingsTab::Picking,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SettingsTab::Employees => "employees",
+            SettingsTab::Absences => "absences",
+            SettingsTab::Import => "import",
+            SettingsTab::Picking => "picking",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingsTab::Employees => "Employees",
+            SettingsTab::Absences => "Absences",
+            SettingsTab::Import => "Import / Export",
+            SettingsTab::Picking => "Picking",
+        }
+    }
+
+    fn from_str(tab: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|other| other.as_str() == tab)
+    }
+}
+
+// ------ Query ------
+
+/// Parameters of a query string, without the leading `?`.
+struct Query<'a>(Vec<(&'a str, &'a str)>);
+
+impl<'a> Query<'a> {
+    fn parse(search: &'a str) -> Self {
+        Query(
+            search
+                .split('&')
+                .filter(|parameter| !parameter.is_empty())
+                .map(|parameter| {
+                    let mut parts = parameter.splitn(2, '=');
+                    (
+                        parts.next().unwrap_or_default(),
+                        parts.next().unwrap_or_default(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.0
+            .iter()
+            .find(|(other, _)| *other == key)
+            .map(|(_, value)| *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+    use uuid::Uuid;
+
+    fn all_routes() -> Vec<Route> {
+        let group = "team-ops".to_owned();
+        let employee_id = Uuid::new_v4();
+
+        let mut routes = vec![
+            Route::Root,
+            Route::Login,
+            Route::Home(group.clone()),
+            Route::Session(group.clone()),
+            Route::Employee(group.clone(), employee_id),
+            Route::history(group.clone()),
+            Route::History {
+                group: group.clone(),
+                page: 3,
+                person: None,
+            },
+            Route::History {
+                group: group.clone(),
+                page: 1,
+                person: Some(employee_id),
+            },
+            Route::History {
+                group: group.clone(),
+                page: 2,
+                person: Some(employee_id),
+            },
+        ];
+        routes.extend(SettingsTab::ALL.iter().map(|tab| Route::Settings {
+            group: group.clone(),
+            tab: *tab,
+        }));
+        routes
+    }
+
+    #[test]
+    fn route_to_url_to_route_is_identity() {
+        for route in all_routes() {
+            let url: seed::Url = route.clone().into();
+            assert_eq!(url.try_into(), Ok(route));
+        }
+    }
+
+    #[test]
+    fn display_matches_url() {
+        for route in all_routes() {
+            let url: seed::Url = route.clone().into();
+            let expected = match &url.search {
+                Some(search) => format!("/{}?{}", url.path.join("/"), search),
+                None => format!("/{}", url.path.join("/")),
+            };
+            assert_eq!(route.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn default_parameters_are_left_out() {
+        assert_eq!(Route::history("a".to_owned()).to_string(), "/g/a/history");
+        assert_eq!(Route::settings("a".to_owned()).to_string(), "/g/a/settings");
+    }
+
+    #[test]
+    fn query_parameters_are_parsed_in_any_order() {
+        let employee_id = Uuid::new_v4();
+        let url = seed::Url::new(vec!["g", "a", "history"])
+            .search(&format!("person={}&page=2", employee_id));
+        assert_eq!(
+            url.try_into(),
+            Ok(Route::History {
+                group: "a".to_owned(),
+                page: 2,
+                person: Some(employee_id),
+            })
+        );
+    }
 
-        match path.next().as_ref().map(String::as_str) {
-            None | Some("") => Some(Route::Home),
-            Some("settings") => Some(Route::Settings),
-            _ => None,
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        let urls = vec![
+            seed::Url::new(vec!["g", "a", "employees", "not-a-uuid"]),
+            seed::Url::new(vec!["g", "a", "history"]).search("page=first"),
+            seed::Url::new(vec!["g", "a", "history"]).search("page=0"),
+            seed::Url::new(vec!["g", "a", "history"]).search("person=nobody"),
+            seed::Url::new(vec!["g", "a", "settings", "unknown"]),
+        ];
+        for url in urls {
+            assert_eq!(Route::try_from(url), Err(()));
         }
-        .ok_or(())
     }
 }
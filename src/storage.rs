@@ -0,0 +1,256 @@
+//! What is kept in the local storage between two visits: the preferences, the session,
+//! the last data loaded for every group so that pages show it while reloading it,
+//! the talk timer of every group and the changes made offline.
+//!
+//! Every value is saved with the version of its schema. Preferences and changes of older schemas
+//! are migrated, cached data of another schema is dropped since it's loaded again anyway.
+
+use crate::outbox::Mutation;
+use crate::preferences::Preferences;
+use crate::theme::Theme;
+use crate::timer::{Stage, Timer, TimerState};
+use caliaconf_api::endpoint::{RoundState, Session};
+use caliaconf_api::entity::{Employee, EmployeeId, GroupSlug, User};
+use caliaconf_api::pick::PickStrategy;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use web_sys::Storage;
+
+const PREFERENCES_KEY: &str = "caliaconf:preferences";
+/// Only the theme was saved, as a plain string, before the preferences.
+const LEGACY_THEME_KEY: &str = "caliaconf:theme";
+const SESSION_KEY: &str = "caliaconf:session";
+const OUTBOX_KEY: &str = "caliaconf:outbox";
+const STAGE_PREFIX: &str = "caliaconf:stage";
+const CACHE_PREFIX: &str = "caliaconf:cache";
+const CACHE_VERSION: u32 = 1;
+
+// ------ Preferences ------
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "version")]
+enum StoredPreferences {
+    #[serde(rename = "1")]
+    V1 {
+        pick_strategy: PickStrategy,
+        theme: Theme,
+        group: Option<GroupSlug>,
+    },
+}
+
+impl From<StoredPreferences> for Preferences {
+    fn from(preferences: StoredPreferences) -> Self {
+        match preferences {
+            StoredPreferences::V1 {
+                pick_strategy,
+                theme,
+                group,
+            } => Preferences {
+                pick_strategy,
+                theme,
+                group,
+            },
+        }
+    }
+}
+
+pub fn load_preferences() -> Preferences {
+    let storage = match local_storage() {
+        Some(storage) => storage,
+        None => return Preferences::default(),
+    };
+    if let Some(preferences) = load::<StoredPreferences>(&storage, PREFERENCES_KEY) {
+        return preferences.into();
+    }
+    // Before the versioned preferences.
+    let theme = storage
+        .get_item(LEGACY_THEME_KEY)
+        .ok()
+        .flatten()
+        .and_then(|theme| serde_json::from_value(serde_json::Value::String(theme)).ok());
+    let _ = storage.remove_item(LEGACY_THEME_KEY);
+    let preferences = Preferences {
+        theme: theme.unwrap_or_default(),
+        ..Preferences::default()
+    };
+    save_preferences(&preferences);
+    preferences
+}
+
+pub fn save_preferences(preferences: &Preferences) {
+    if let Some(storage) = local_storage() {
+        let preferences = StoredPreferences::V1 {
+            pick_strategy: preferences.pick_strategy,
+            theme: preferences.theme,
+            group: preferences.group.clone(),
+        };
+        save(&storage, PREFERENCES_KEY, &preferences);
+    }
+}
+
+// ------ Session ------
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "version")]
+enum StoredSession {
+    #[serde(rename = "1")]
+    V1 { token: String, user: User },
+}
+
+pub fn load_session() -> Option<Session> {
+    match local_storage().and_then(|storage| load(&storage, SESSION_KEY))? {
+        StoredSession::V1 { token, user } => Some(Session { token, user }),
+    }
+}
+
+pub fn save_session(session: &Session) {
+    if let Some(storage) = local_storage() {
+        let session = StoredSession::V1 {
+            token: session.token.clone(),
+            user: session.user.clone(),
+        };
+        save(&storage, SESSION_KEY, &session);
+    }
+}
+
+pub fn forget_session() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(SESSION_KEY);
+    }
+}
+
+// ------ Stage ------
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "version")]
+enum StoredStage {
+    #[serde(rename = "1")]
+    V1 {
+        speaker: Option<EmployeeId>,
+        duration: u32,
+        warnings: Vec<u32>,
+        state: TimerState,
+    },
+}
+
+pub fn load_stage(group: &str) -> Stage {
+    match local_storage().and_then(|storage| load(&storage, &stage_key(group))) {
+        Some(StoredStage::V1 {
+            speaker,
+            duration,
+            warnings,
+            state,
+        }) => Stage {
+            speaker,
+            timer: Timer {
+                state,
+                ..Timer::new(duration, warnings)
+            },
+        },
+        None => Stage::default(),
+    }
+}
+
+pub fn save_stage(group: &str, stage: &Stage) {
+    if let Some(storage) = local_storage() {
+        let stage = StoredStage::V1 {
+            speaker: stage.speaker,
+            duration: stage.timer.duration,
+            warnings: stage.timer.warnings.clone(),
+            state: stage.timer.state,
+        };
+        save(&storage, &stage_key(group), &stage);
+    }
+}
+
+fn stage_key(group: &str) -> String {
+    format!("{}:{}", STAGE_PREFIX, group)
+}
+
+// ------ Outbox ------
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "version")]
+enum StoredOutbox {
+    #[serde(rename = "1")]
+    V1 { mutations: Vec<Mutation> },
+}
+
+pub fn load_outbox() -> Vec<Mutation> {
+    match local_storage().and_then(|storage| load(&storage, OUTBOX_KEY)) {
+        Some(StoredOutbox::V1 { mutations }) => mutations,
+        None => Vec::new(),
+    }
+}
+
+pub fn save_outbox(mutations: &[Mutation]) {
+    if let Some(storage) = local_storage() {
+        let outbox = StoredOutbox::V1 {
+            mutations: mutations.to_vec(),
+        };
+        save(&storage, OUTBOX_KEY, &outbox);
+    }
+}
+
+// ------ Cache ------
+
+#[derive(Serialize, Deserialize)]
+struct Cached<T> {
+    version: u32,
+    data: T,
+}
+
+pub fn cached_employees(group: &str) -> Option<Vec<Employee>> {
+    load_cached(group, "employees")
+}
+
+pub fn cache_employees(group: &str, employees: &[Employee]) {
+    save_cached(group, "employees", employees);
+}
+
+pub fn cached_round(group: &str) -> Option<RoundState> {
+    load_cached(group, "round")
+}
+
+pub fn cache_round(group: &str, round: &RoundState) {
+    save_cached(group, "round", round);
+}
+
+fn load_cached<T: DeserializeOwned>(group: &str, name: &str) -> Option<T> {
+    let storage = local_storage()?;
+    load::<Cached<T>>(&storage, &cache_key(group, name))
+        .filter(|cached| cached.version == CACHE_VERSION)
+        .map(|cached| cached.data)
+}
+
+fn save_cached<T: Serialize + ?Sized>(group: &str, name: &str, data: &T) {
+    if let Some(storage) = local_storage() {
+        let cached = Cached {
+            version: CACHE_VERSION,
+            data,
+        };
+        save(&storage, &cache_key(group, name), &cached);
+    }
+}
+
+fn cache_key(group: &str, name: &str) -> String {
+    format!("{}:{}:{}", CACHE_PREFIX, group, name)
+}
+
+// ------ local storage ------
+
+fn local_storage() -> Option<Storage> {
+    seed::storage::get_storage()
+}
+
+/// Unreadable values, of an unknown schema, are ignored.
+fn load<T: DeserializeOwned>(storage: &Storage, key: &str) -> Option<T> {
+    let json = storage.get_item(key).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// The local storage may be full or disabled, the value is not kept then.
+fn save<T: Serialize + ?Sized>(storage: &Storage, key: &str, value: &T) {
+    if let Ok(json) = serde_json::to_string(value) {
+        let _ = storage.set_item(key, &json);
+    }
+}
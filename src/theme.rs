@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// The `link` of `index.html` loading the dark stylesheet.
+const DARK_STYLESHEET_ID: &str = "dark-theme";
+
+// ------ Theme ------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    /// Follows `prefers-color-scheme`.
+    #[default]
+    System,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::System];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::System => "System",
+        }
+    }
+
+    /// Font Awesome icon of the theme.
+    pub fn icon(self) -> &'static str {
+        match self {
+            Theme::Light => "fa-sun",
+            Theme::Dark => "fa-moon",
+            Theme::System => "fa-adjust",
+        }
+    }
+
+    /// Switches the dark stylesheet and the `data-theme` attribute the styles of `index.html` rely on.
+    pub fn apply(self) {
+        let document = seed::document();
+        if let Some(root) = document.document_element() {
+            let _ = match self {
+                Theme::System => root.remove_attribute("data-theme"),
+                _ => root.set_attribute("data-theme", self.as_str()),
+            };
+        }
+        if let Some(stylesheet) = document.get_element_by_id(DARK_STYLESHEET_ID) {
+            let media = match self {
+                Theme::Light => "not all",
+                Theme::Dark => "all",
+                Theme::System => "(prefers-color-scheme: dark)",
+            };
+            let _ = stylesheet.set_attribute("media", media);
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::System => "system",
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Countdown of the talk being given. It runs on the wall clock, so that it goes on
+//! while the page is reloaded or closed.
+
+use caliaconf_api::entity::EmployeeId;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, AudioScheduledSourceNode};
+
+const DEFAULT_DURATION: u32 = 20 * 60;
+const DEFAULT_WARNINGS: [u32; 2] = [5 * 60, 60];
+
+/// Milliseconds since the epoch.
+pub fn now() -> f64 {
+    js_sys::Date::now()
+}
+
+// ------ Stage ------
+
+/// What the session page of a group keeps between two visits.
+#[derive(Clone, Debug, Default)]
+pub struct Stage {
+    /// `None` until the page is first opened, the last pick speaks then.
+    pub speaker: Option<EmployeeId>,
+    pub timer: Timer,
+}
+
+// ------ Timer ------
+
+#[derive(Clone, Debug)]
+pub struct Timer {
+    /// Length of a talk, in seconds.
+    pub duration: u32,
+    /// Seconds left when the speaker is warned, longest first.
+    pub warnings: Vec<u32>,
+    pub state: TimerState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TimerState {
+    Stopped,
+    Running {
+        /// When it was started or resumed.
+        since: f64,
+        /// Milliseconds elapsed before.
+        elapsed: f64,
+    },
+    Paused {
+        elapsed: f64,
+    },
+}
+
+/// How close the end of the talk is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Normal,
+    /// Less time left than the longest warning.
+    Warning,
+    /// Less time left than the shortest warning.
+    Critical,
+    Overtime,
+}
+
+impl Timer {
+    pub fn new(duration: u32, warnings: Vec<u32>) -> Self {
+        let mut timer = Self {
+            duration,
+            warnings: Vec::new(),
+            state: TimerState::Stopped,
+        };
+        timer.set_warnings(warnings);
+        timer
+    }
+
+    pub fn set_warnings(&mut self, mut warnings: Vec<u32>) {
+        warnings.sort_unstable_by(|a, b| b.cmp(a));
+        warnings.dedup();
+        self.warnings = warnings;
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, TimerState::Running { .. })
+    }
+
+    /// Starts, or resumes, at `now`.
+    pub fn start(&mut self, now: f64) {
+        let elapsed = self.elapsed(now);
+        self.state = TimerState::Running {
+            since: now,
+            elapsed,
+        };
+    }
+
+    pub fn pause(&mut self, now: f64) {
+        self.state = TimerState::Paused {
+            elapsed: self.elapsed(now),
+        };
+    }
+
+    pub fn reset(&mut self) {
+        self.state = TimerState::Stopped;
+    }
+
+    /// Milliseconds the timer has been running at `now`.
+    pub fn elapsed(&self, now: f64) -> f64 {
+        match self.state {
+            TimerState::Stopped => 0.,
+            TimerState::Running { since, elapsed } => elapsed + (now - since).max(0.),
+            TimerState::Paused { elapsed } => elapsed,
+        }
+    }
+
+    /// Seconds left at `now`, negative once the talk is over time.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn remaining(&self, now: f64) -> i64 {
+        i64::from(self.duration) - (self.elapsed(now) / 1000.).floor() as i64
+    }
+
+    pub fn level(&self, now: f64) -> Level {
+        let remaining = self.remaining(now);
+        let below =
+            |warning: Option<&u32>| warning.is_some_and(|warning| remaining <= i64::from(*warning));
+        if remaining <= 0 {
+            Level::Overtime
+        } else if below(self.warnings.last()) {
+            Level::Critical
+        } else if below(self.warnings.first()) {
+            Level::Warning
+        } else {
+            Level::Normal
+        }
+    }
+
+    /// Number of beeps to ring when `remaining` seconds are left, `previous` being left before:
+    /// one for a warning crossed, three for the end of the talk.
+    pub fn beeps(&self, previous: i64, remaining: i64) -> u32 {
+        if previous > 0 && remaining <= 0 {
+            3
+        } else if self
+            .warnings
+            .iter()
+            .any(|warning| previous > i64::from(*warning) && remaining <= i64::from(*warning))
+        {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new(DEFAULT_DURATION, DEFAULT_WARNINGS.to_vec())
+    }
+}
+
+// ------ alarm ------
+
+/// Rings `beeps` short beeps with the `audio` context, created on a click since browsers
+/// only let pages play sounds once the user has interacted with them.
+pub fn ring(audio: &AudioContext, beeps: u32) -> Result<(), JsValue> {
+    let start = audio.current_time();
+    for beep in 0..beeps {
+        let oscillator = audio.create_oscillator()?;
+        oscillator.frequency().set_value(880.);
+        let gain = audio.create_gain()?;
+        gain.gain().set_value(0.2);
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&audio.destination())?;
+
+        let at = start + f64::from(beep) * 0.4;
+        AudioScheduledSourceNode::start_with_when(&oscillator, at)?;
+        AudioScheduledSourceNode::stop_with_when(&oscillator, at + 0.25)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+// Keeps the app usable without a connection: the app shell is cached when the service worker
+// is installed, and the stylesheets and scripts of the CDNs the first time they are loaded.
+// Requests to the API are never cached, the app keeps its own data in the local storage.
+
+// Bumped to drop the API responses cached by the previous versions.
+const CACHE = 'caliaconf-v2';
+const APP_SHELL = [
+    '/',
+    '/index.html',
+    '/manifest.webmanifest',
+    '/icon.svg',
+    '/pkg/package.js',
+    '/pkg/package_bg.wasm',
+];
+const CACHED_DESTINATIONS = ['style', 'script', 'font', 'image'];
+// Paths of the API when it's served from the same origin as the app: `login`, `groups`
+// and the endpoints of a group, `events` included.
+const API_PATH = /(^|\/)(login|groups(\/add)?|g\/[^/]+\/.+)$/;
+
+self.addEventListener('install', event => {
+    event.waitUntil(
+        caches.open(CACHE)
+            .then(cache => cache.addAll(APP_SHELL))
+            .then(() => self.skipWaiting())
+    );
+});
+
+self.addEventListener('activate', event => {
+    event.waitUntil(
+        caches.keys()
+            .then(keys => Promise.all(keys.filter(key => key !== CACHE).map(key => caches.delete(key))))
+            .then(() => self.clients.claim())
+    );
+});
+
+self.addEventListener('fetch', event => {
+    const request = event.request;
+    if (request.method !== 'GET') {
+        return;
+    }
+    const url = new URL(request.url);
+    if (request.mode !== 'navigate' && isApiRequest(request, url)) {
+        return;
+    }
+
+    if (request.mode === 'navigate') {
+        // Every route is rendered by index.html.
+        event.respondWith(networkFirst(request, '/index.html'));
+    } else if (url.origin === self.location.origin) {
+        event.respondWith(networkFirst(request, request));
+    } else if (CACHED_DESTINATIONS.includes(request.destination)) {
+        event.respondWith(cacheFirst(request));
+    }
+});
+
+// Authenticated requests, and the never-ending event streams, whatever their path.
+function isApiRequest(request, url) {
+    return request.headers.has('Authorization')
+        || (request.headers.get('Accept') || '').includes('text/event-stream')
+        || (url.origin === self.location.origin && API_PATH.test(url.pathname));
+}
+
+function isEventStream(response) {
+    return (response.headers.get('Content-Type') || '').startsWith('text/event-stream');
+}
+
+// The latest version when online, the cached one otherwise.
+function networkFirst(request, cacheKey) {
+    return fetch(request)
+        .then(response => {
+            if (response.ok && !isEventStream(response)) {
+                const copy = response.clone();
+                caches.open(CACHE).then(cache => cache.put(cacheKey, copy));
+            }
+            return response;
+        })
+        .catch(() => caches.match(cacheKey));
+}
+
+function cacheFirst(request) {
+    return caches.match(request).then(cached => cached || fetch(request).then(response => {
+        const copy = response.clone();
+        caches.open(CACHE).then(cache => cache.put(request, copy));
+        return response;
+    }));
+}